@@ -0,0 +1,82 @@
+//! Costs of reloading and cloning a large model, with owned titles as before interning and
+//! interned ones as the providers now produce, and of scoring it against a query.  Run with
+//! `cargo bench -p common`.
+use std::sync::Arc;
+
+use common::{FuzzyMatcher, Matcher};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+/// Roughly a busy session's worth of windows, or a well stocked PATH
+const MODEL_LEN: usize = 5000;
+
+fn titles() -> Vec<String> {
+    (0..MODEL_LEN)
+        .map(|index| format!("Terminal - ~/src/project-{} - vim", index))
+        .collect()
+}
+
+/// A refresh turns the titles read from IPC or the filesystem into a new model
+fn bench_refresh(c: &mut Criterion) {
+    let titles = titles();
+    // The previous model keeps the interned titles alive, as the running launcher would
+    let _previous: Vec<Arc<str>> = titles.iter().map(|title| common::intern(title)).collect();
+
+    let mut group = c.benchmark_group("refresh");
+    group.bench_function("owned", |b| {
+        b.iter(|| {
+            black_box(&titles)
+                .iter()
+                .map(|title| title.to_string())
+                .collect::<Vec<String>>()
+        })
+    });
+    group.bench_function("interned", |b| {
+        b.iter(|| {
+            black_box(&titles)
+                .iter()
+                .map(|title| common::intern(title))
+                .collect::<Vec<Arc<str>>>()
+        })
+    });
+    group.finish();
+}
+
+/// Reloads and background tasks clone `State.apps`
+fn bench_clone_model(c: &mut Criterion) {
+    let owned = titles();
+    let interned: Vec<Arc<str>> = owned.iter().map(|title| common::intern(title)).collect();
+
+    let mut group = c.benchmark_group("clone_model");
+    group.bench_function("owned", |b| b.iter(|| black_box(&owned).clone()));
+    group.bench_function("interned", |b| b.iter(|| black_box(&interned).clone()));
+    group.finish();
+}
+
+/// Each keystroke scores every item against the query, but highlights only the rows drawn
+fn bench_fuzzy(c: &mut Criterion) {
+    let titles = titles();
+    let matcher = FuzzyMatcher::default();
+
+    let mut group = c.benchmark_group("fuzzy");
+    group.bench_function("score", |b| {
+        b.iter(|| {
+            black_box(&titles)
+                .iter()
+                .filter_map(|title| matcher.score("proj vim", title))
+                .count()
+        })
+    });
+    group.bench_function("highlight_rows", |b| {
+        b.iter(|| {
+            black_box(&titles)
+                .iter()
+                .take(20)
+                .map(|title| matcher.highlights("proj vim", title))
+                .collect::<Vec<_>>()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_refresh, bench_clone_model, bench_fuzzy);
+criterion_main!(benches);
@@ -0,0 +1,41 @@
+# Line under the bar counting down the timeout, which closes the launcher or takes the selection
+timeout-close = wird in { $secs } s geschlossen
+timeout-take = Auswahl wird in { $secs } s übernommen
+# Status line shown until the items are first loaded
+loading = wird geladen…
+# Status line shown while items are reloaded in the background
+refreshing = wird aktualisiert…
+# Pinned row creating something named by the query
+create-row = „{ $query }“ erstellen
+# Shown in place of the list when nothing matches the query
+no-matches = Keine Treffer – Esc bricht ab
+# Pinned row running the query as a command line when nothing matches it
+run-row = „{ $query }“ ausführen
+# How to answer the question asked before taking an item
+confirm-hint = Eingabe bestätigt, Esc geht zurück
+# Banner shown when the query is not a valid regular expression
+regex-invalid = Ungültiges Muster: { $error }
+# Banner shown when the item selected can't be taken with the action given on the command line
+action-missing = { $item } hat keine Aktion { $action }
+
+## Command palette
+command-switch-theme = Design wechseln
+command-toggle-case-sensitivity = Groß-/Kleinschreibung umschalten
+command-toggle-details = Details umschalten
+command-reload-items = Einträge neu laden
+command-open-config = Konfiguration öffnen
+
+## Entry placeholders of each front-end
+hint-drun = Anwendung
+hint-dmenu = dmenu
+hint-windows = Fenster
+hint-autostart = Autostart
+hint-media = Medien
+hint-mpd = Musik
+hint-context = Kontext
+hint-proc = Prozesse
+hint-adjust = Anpassen
+hint-repos = Repositorys
+hint-run = Ausführen
+hint-open = Öffnen
+hint-save = Speichern
@@ -0,0 +1,41 @@
+# Line under the bar counting down the timeout, which closes the launcher or takes the selection
+timeout-close = closing in { $secs } s
+timeout-take = taking the selection in { $secs } s
+# Status line shown until the items are first loaded
+loading = loading…
+# Status line shown while items are reloaded in the background
+refreshing = refreshing…
+# Pinned row creating something named by the query
+create-row = Create '{ $query }'
+# Shown in place of the list when nothing matches the query
+no-matches = No matches — press Esc to cancel
+# Pinned row running the query as a command line when nothing matches it
+run-row = Run '{ $query }'
+# How to answer the question asked before taking an item
+confirm-hint = Enter to confirm, Esc to go back
+# Banner shown when the query is not a valid regular expression
+regex-invalid = Invalid pattern: { $error }
+# Banner shown when the item selected can't be taken with the action given on the command line
+action-missing = { $item } has no { $action } action
+
+## Command palette
+command-switch-theme = Switch theme
+command-toggle-case-sensitivity = Toggle case sensitivity
+command-toggle-details = Toggle details
+command-reload-items = Reload items
+command-open-config = Open config
+
+## Entry placeholders of each front-end
+hint-drun = drun
+hint-dmenu = dmenu
+hint-windows = window
+hint-autostart = autostart
+hint-media = media
+hint-mpd = music
+hint-context = context
+hint-proc = processes
+hint-adjust = adjust
+hint-repos = repositories
+hint-run = run
+hint-open = open
+hint-save = save
@@ -0,0 +1,41 @@
+//! The flags every launcher front-end takes, flattened into the command line of each
+use clap::Args;
+
+#[derive(Debug, Clone, Args)]
+pub struct CommonArgs {
+    /// Placeholder of the query entry, in place of the one configured under `prompts` or its own
+    #[arg(short, long)]
+    pub prompt: Option<String>,
+    /// Stay open until an item is taken, for dedicated terminals: Escape clears the query, and
+    /// losing focus doesn't close the launcher
+    #[arg(long)]
+    pub kiosk: bool,
+    /// Print to stderr when each phase of startup ends, such as loading the items and drawing
+    /// the first frame, to attach to reports of slowness
+    #[arg(long)]
+    pub profile: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+
+    #[derive(Debug, Parser)]
+    struct FrontEnd {
+        #[arg(long)]
+        docker: bool,
+        #[command(flatten)]
+        common: CommonArgs,
+    }
+
+    #[test]
+    fn test_flattened() {
+        let args = FrontEnd::parse_from(["ilia-context", "-p", "ctx", "--kiosk", "--docker"]);
+        assert!(args.docker);
+        assert_eq!(args.common.prompt.as_deref(), Some("ctx"));
+        assert!(args.common.kiosk);
+        assert!(!args.common.profile);
+    }
+}
@@ -0,0 +1,51 @@
+//! Copying to the Wayland clipboard with wl-copy, clearing secrets once they have had time to be
+//! pasted
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+use anyhow::Context;
+
+/// Put the text on the clipboard
+pub fn copy_to_clipboard(text: &str) -> anyhow::Result<()> {
+    pipe(Command::new("wl-copy"), text)
+}
+
+/// Put a secret, such as a password, on the clipboard and clear it after a delay unless something
+/// else was copied meanwhile.  Clipboard managers are asked not to keep it.  Never cleared when
+/// the delay is zero, as when `Config::clipboard_clear_ms` is 0.
+pub fn copy_secret_to_clipboard(text: &str, clear_after: Duration) -> anyhow::Result<()> {
+    let mut copy = Command::new("wl-copy");
+    copy.args(["--type", "text/plain", "--sensitive"]);
+    pipe(copy, text)?;
+    if clear_after.is_zero() {
+        return Ok(());
+    }
+
+    // The launcher exits right after copying, so a shell outlives it to do the clearing.  The
+    // secret goes through stdin rather than the arguments, which any user can list.
+    let mut clear = Command::new("sh");
+    clear.args([
+        "-c",
+        r#"secret=$(cat); sleep "$1"; [ "$(wl-paste --no-newline 2>/dev/null)" = "$secret" ] && wl-copy --clear"#,
+        "sh",
+        &format!("{:.3}", clear_after.as_secs_f64()),
+    ]);
+    pipe(clear, text)
+}
+
+// Start the command with the text on its stdin, without waiting for it to finish
+fn pipe(mut command: Command, text: &str) -> anyhow::Result<()> {
+    let program = command.get_program().to_string_lossy().into_owned();
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .spawn()
+        .with_context(|| format!("Failed to run {}", program))?;
+    child
+        .stdin
+        .take()
+        .context("No stdin")?
+        .write_all(text.as_bytes())
+        .with_context(|| format!("Failed to write to {}", program))
+}
@@ -0,0 +1,253 @@
+//! User configuration shared by every front-end, read from `$XDG_CONFIG_HOME/ilia/config.toml`
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Instant;
+
+use anyhow::Context;
+use iced::Length;
+use serde::Deserialize;
+
+use crate::profile;
+use crate::row::Ellipsis;
+
+/// Where the configuration is read from, for the help of every front-end
+pub const CONFIG_HELP: &str = "Reads its configuration from $XDG_CONFIG_HOME/ilia/config.toml, \
+    shared by every ilia front-end. Missing keys take their default values.";
+
+/// The contents of the configuration file.  Missing keys take their default values.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Name of the iced theme to use, e.g. "Nord" or "Tokyo Night"
+    pub theme: Option<String>,
+    /// Names of the themes F8 cycles through, every built-in theme when empty
+    pub themes: Vec<String>,
+    /// Icon theme to draw the icons of items from, such as "Papirus", or GTK's when unset
+    pub icon_theme: Option<String>,
+    /// Match the query against items exactly rather than ignoring case
+    pub case_sensitive: bool,
+    /// Unless `case_sensitive`, match case sensitively only when the query has an uppercase letter
+    pub smart_case: bool,
+    /// Front-ends showing no query entry, such as `["ilia-dmenu"]` for fixed menus, so the arrow
+    /// keys and Enter are the only interaction
+    pub hide_entry: Vec<String>,
+    /// Front-ends cutting long titles in their middle rather than their end, keeping the app
+    /// name window titles end with, such as `["ilia-windows"]`
+    pub ellipsize_middle: Vec<String>,
+    /// Placeholders of the query entry by front-end, in place of their own, such as
+    /// `ilia-drun = "apps"` under `[prompts]`.  A front-end's `--prompt` takes precedence.
+    pub prompts: HashMap<String, String>,
+    /// Width of the query entry in pixels, filling the window when unset
+    pub entry_width: Option<f32>,
+    /// Width of the item list in pixels, filling the window when unset
+    pub list_width: Option<f32>,
+    /// Items per line of the list, laying them out in a grid navigated with all four arrow keys
+    /// when more than 1, such as 5 for launching apps by their icons.  The window widens to fit.
+    pub columns: usize,
+    /// Move the selection past the last item to the first and before the first to the last,
+    /// rather than stopping.  Up from the first row then wraps instead of recalling a query.
+    pub wrap_around: bool,
+    /// Step the selection with the mouse wheel, the list scrolling to follow it as in rofi,
+    /// rather than scroll the list alone
+    pub wheel_selects: bool,
+    /// Show the details of the selected item under the list, such as the command a desktop
+    /// entry runs, from launch.  F2 or the palette toggles them.
+    pub details: bool,
+    /// Milliseconds after startup during which losing focus does not close the window, to ride
+    /// out compositor focus jitter when launched from a key binding, and during which the
+    /// modifiers held are taken as the binding's, for switching like alt-tab
+    pub focus_grace_ms: u64,
+    /// Cover the output with a backdrop, dismissing the launcher when it is clicked
+    pub backdrop: bool,
+    /// How strongly the backdrop dims the desktop, from 0 (clear) to 1 (black)
+    pub backdrop_opacity: f32,
+    /// Rows listed at most, the best matches first, so huge lists such as piped `locate` output
+    /// stay responsive.  The others are found by refining the query.  0, the default, lists every
+    /// match.
+    pub max_rows: usize,
+    /// Rows kept visible above and below the selection while navigating, like vim's scrolloff
+    pub scroll_margin: usize,
+    /// Milliseconds after a cancel during which reopening restores the query, in modes which
+    /// keep it.  0 disables restoring.
+    pub query_restore_ms: u64,
+    /// Queries which took an item kept by each front-end, recalled with Ctrl+Up and Ctrl+Down,
+    /// or Up from the first row of an empty query.  0 keeps none.
+    pub history_size: usize,
+    /// Milliseconds after which a secret copied by a mode, such as a password, is cleared from
+    /// the clipboard.  0 keeps it.
+    pub clipboard_clear_ms: u64,
+    /// Keep the screen from idling or locking while the launcher is open
+    pub inhibit_idle: bool,
+    /// Match the query as a regular expression.  A query starting with `/` always is one.
+    pub regex: bool,
+    /// Start apps without the launcher's own environment variables, blocked signals, niceness
+    /// or session, as if started from the session
+    pub sanitize_launch: bool,
+    /// Further environment variables to remove when sanitizing, a trailing `*` matching any
+    /// suffix
+    pub launch_env_remove: Vec<String>,
+    /// Environment variables to keep when sanitizing, even if otherwise removed
+    pub launch_env_keep: Vec<String>,
+    /// Glob patterns of the command lines each front-end may run, such as
+    /// `ilia-dmenu = ["/opt/kiosk/*"]` under `[allowed_commands]` for locked-down setups, where
+    /// `*` matches any text and `?` one character.  ilia-dmenu's are the lines it may print.
+    /// Front-ends not listed run anything.
+    pub allowed_commands: HashMap<String, Vec<String>>,
+    /// Shown in place of the list when nothing matches the query, "No matches — press Esc to
+    /// cancel" in the user's language when unset
+    pub no_matches_message: Option<String>,
+    /// When nothing matches the query, offer to run it as a shell command line
+    pub run_unmatched: bool,
+    /// Queries which, typed exactly, list the item of the id first however it matches, such as
+    /// `ff = "firefox"` for the desktop entry `firefox.desktop`
+    pub aliases: HashMap<String, String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            theme: None,
+            themes: vec![],
+            icon_theme: None,
+            case_sensitive: false,
+            smart_case: true,
+            hide_entry: vec![],
+            ellipsize_middle: vec![],
+            prompts: HashMap::new(),
+            entry_width: None,
+            list_width: None,
+            columns: 1,
+            wrap_around: false,
+            wheel_selects: false,
+            details: false,
+            focus_grace_ms: 250,
+            backdrop: false,
+            backdrop_opacity: 0.4,
+            max_rows: 0,
+            scroll_margin: 1,
+            query_restore_ms: 3000,
+            history_size: 50,
+            clipboard_clear_ms: 45000,
+            inhibit_idle: false,
+            regex: false,
+            sanitize_launch: false,
+            launch_env_remove: vec![],
+            launch_env_keep: vec![],
+            allowed_commands: HashMap::new(),
+            no_matches_message: None,
+            run_unmatched: false,
+            aliases: HashMap::new(),
+        }
+    }
+}
+
+/// A configured width in pixels, or the full window width if unset
+fn width(pixels: Option<f32>) -> Length {
+    pixels.map_or(Length::Fill, Length::Fixed)
+}
+
+impl Config {
+    pub fn entry_length(&self) -> Length {
+        width(self.entry_width)
+    }
+
+    pub fn list_length(&self) -> Length {
+        width(self.list_width)
+    }
+
+    /// Whether the front-end of the program name shows no query entry
+    pub fn hides_entry(&self, program_name: &str) -> bool {
+        self.hide_entry.iter().any(|name| name == program_name)
+    }
+
+    /// Where the front-end of the program name cuts titles too long for their row
+    pub fn ellipsis(&self, program_name: &str) -> Ellipsis {
+        if self
+            .ellipsize_middle
+            .iter()
+            .any(|name| name == program_name)
+        {
+            Ellipsis::Middle
+        } else {
+            Ellipsis::End
+        }
+    }
+
+    /// The placeholder of the query entry configured for the front-end of the program name, or
+    /// the given one of its own
+    pub fn prompt(&self, program_name: &str, own: String) -> String {
+        self.prompts.get(program_name).cloned().unwrap_or(own)
+    }
+
+    /// Location of the configuration file
+    pub fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("ilia").join("config.toml"))
+    }
+
+    /// Load the configuration file, falling back to defaults if it is missing or invalid
+    pub fn load() -> Config {
+        let started = Instant::now();
+        let config = match Self::try_load() {
+            Ok(config) => config,
+            Err(err) => {
+                eprintln!("Ignoring config: {:#}", err);
+                Config::default()
+            }
+        };
+        profile::phase("config_load", Some(started.elapsed()));
+        config
+    }
+
+    /// Set the theme in the configuration file, keeping the rest of the file as it is
+    pub fn save_theme(name: &str) -> anyhow::Result<()> {
+        let path = Self::path().context("No config directory")?;
+        let contents = match std::fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(err) => return Err(err).context("Failed to read config"),
+        };
+        let contents = with_theme(&contents, name)
+            .with_context(|| format!("Invalid config {}", path.display()))?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).context("Failed to create config dir")?;
+        }
+        std::fs::write(&path, contents).context("Failed to write config")
+    }
+
+    fn try_load() -> anyhow::Result<Config> {
+        let Some(path) = Self::path().filter(|path| path.exists()) else {
+            return Ok(Config::default());
+        };
+        let contents = std::fs::read_to_string(&path).context("Failed to read config")?;
+        toml::from_str(&contents).with_context(|| format!("Invalid config {}", path.display()))
+    }
+}
+
+// The configuration file contents with the theme set to `name`
+fn with_theme(contents: &str, name: &str) -> anyhow::Result<String> {
+    let mut document: toml_edit::DocumentMut = contents.parse()?;
+    document["theme"] = toml_edit::value(name);
+    Ok(document.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_with_theme() {
+        assert_eq!(with_theme("", "Dracula").unwrap(), "theme = \"Dracula\"\n");
+        let contents = "# Mine\ntheme = \"Nord\" # cold\nscroll_margin = 2\n";
+        let updated = with_theme(contents, "Tokyo Night").unwrap();
+        assert!(updated.starts_with("# Mine\ntheme = \"Tokyo Night\""));
+        assert!(updated.ends_with("scroll_margin = 2\n"));
+        assert!(with_theme("theme = ", "Nord").is_err());
+    }
+
+    #[test]
+    fn test_prompt() {
+        let config: Config = toml::from_str("[prompts]\nilia-drun = \"apps\"\n").unwrap();
+        assert_eq!(config.prompt("ilia-drun", String::from("drun")), "apps");
+        assert_eq!(config.prompt("ilia-mpd", String::from("music")), "music");
+    }
+}
@@ -0,0 +1,121 @@
+//! Crash reports, so a panic in a launcher started from a hotkey, with no terminal to print to,
+//! leaves behind what happened and says where to find it
+use std::backtrace::Backtrace;
+use std::panic::PanicHookInfo;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::Context;
+
+/// Items in the model when the last one was loaded, for the report
+static MODEL_SIZE: AtomicUsize = AtomicUsize::new(0);
+
+/// Write a report for any panic, besides printing it as usual
+pub(crate) fn install(program_name: &'static str) {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        default_hook(info);
+        let report = report(program_name, info, &Backtrace::force_capture());
+        match write_report(program_name, &report) {
+            Ok(path) => notify(program_name, &path),
+            Err(err) => eprintln!("Unable to write crash report: {:#}", err),
+        }
+    }));
+}
+
+/// Note the size of the loaded model, for any later report
+pub(crate) fn record_model_size(size: usize) {
+    MODEL_SIZE.store(size, Ordering::Relaxed);
+}
+
+fn report(program_name: &str, info: &PanicHookInfo, backtrace: &Backtrace) -> String {
+    let message = info
+        .payload()
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| info.payload().downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("(no message)");
+    let location = info
+        .location()
+        .map_or_else(String::new, |location| format!(" at {}", location));
+    format!(
+        "{} {} crashed on {}\n\nPanic{}: {}\nItems loaded: {}\n\nBacktrace:\n{}\n",
+        program_name,
+        env!("CARGO_PKG_VERSION"),
+        utc_timestamp(now_secs()),
+        location,
+        message,
+        MODEL_SIZE.load(Ordering::Relaxed),
+        backtrace
+    )
+}
+
+// The report goes into $XDG_CACHE_HOME/ilia, named after the mode and time
+fn write_report(program_name: &str, report: &str) -> anyhow::Result<PathBuf> {
+    let dir = dirs::cache_dir()
+        .context("Unable to find cache dir")?
+        .join("ilia");
+    std::fs::create_dir_all(&dir).context("Failed to create cache dir")?;
+    let path = dir.join(format!("crash-{}-{}.txt", program_name, now_secs()));
+    std::fs::write(&path, report).context("Failed to write report")?;
+    eprintln!("Crash report written to {}", path.display());
+    Ok(path)
+}
+
+// Point to the report in a desktop notification, where notify-send is installed
+fn notify(program_name: &str, path: &std::path::Path) {
+    let _ = Command::new("notify-send")
+        .args([
+            "--urgency=critical",
+            &format!("{} crashed", program_name),
+            &format!("A report was written to {}", path.display()),
+        ])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status();
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs())
+}
+
+/// Seconds since the epoch as an ISO 8601 UTC date and time
+fn utc_timestamp(secs: u64) -> String {
+    let (days, secs) = (secs / 86_400, secs % 86_400);
+    // Civil from days, after Howard Hinnant's algorithm
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        secs / 3600,
+        secs % 3600 / 60,
+        secs % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_utc_timestamp() {
+        assert_eq!(utc_timestamp(0), "1970-01-01T00:00:00Z");
+        assert_eq!(utc_timestamp(951_782_400), "2000-02-29T00:00:00Z");
+        assert_eq!(utc_timestamp(1_791_987_245), "2026-10-14T14:14:05Z");
+    }
+}
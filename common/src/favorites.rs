@@ -0,0 +1,72 @@
+//! Items pinned with Ctrl+D to lead the list for the empty query, kept per front-end in
+//! `$XDG_STATE_HOME/ilia`
+use std::path::PathBuf;
+
+use anyhow::Context;
+
+#[derive(Debug, Default)]
+pub struct Favorites {
+    /// Where the favorites are kept, or None to keep them only while open
+    path: Option<PathBuf>,
+    /// Ids of the items, in the order they were pinned
+    ids: Vec<String>,
+}
+
+impl Favorites {
+    /// The favorites of the program
+    pub fn load(program_name: &str) -> Favorites {
+        let path = dirs::state_dir()
+            .map(|dir| dir.join("ilia").join(format!("{}.favorites", program_name)));
+        let ids = path
+            .as_ref()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .map(|contents| contents.lines().map(String::from).collect())
+            .unwrap_or_default();
+        Favorites { path, ids }
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.ids.iter().any(|pinned| pinned == id)
+    }
+
+    /// Pin the item, or unpin it if it is pinned
+    pub fn toggle(&mut self, id: &str) -> anyhow::Result<()> {
+        // Lines are ids
+        if id.contains('\n') {
+            return Ok(());
+        }
+        match self.ids.iter().position(|pinned| pinned == id) {
+            Some(position) => {
+                self.ids.remove(position);
+            }
+            None => self.ids.push(id.to_string()),
+        }
+        self.save()
+    }
+
+    fn save(&self) -> anyhow::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).context("Failed to create state dir")?;
+        }
+        let contents: String = self.ids.iter().map(|id| format!("{}\n", id)).collect();
+        std::fs::write(path, contents).context("Failed to save favorites")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_toggle() {
+        let mut favorites = Favorites::default();
+        favorites.toggle("firefox.desktop").unwrap();
+        favorites.toggle("code.desktop").unwrap();
+        favorites.toggle("firefox.desktop").unwrap();
+        assert!(!favorites.contains("firefox.desktop"));
+        assert!(favorites.contains("code.desktop"));
+    }
+}
@@ -0,0 +1,155 @@
+//! How often and how recently each item was taken, kept per front-end in `$XDG_STATE_HOME/ilia`
+//! to order the items of the empty query
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::Context;
+
+const DAY_SECS: u64 = 24 * 60 * 60;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Use {
+    count: u64,
+    /// Seconds since the epoch of the last use
+    last: u64,
+}
+
+#[derive(Debug, Default)]
+pub struct Frecency {
+    /// Where the uses are kept, or None to keep them only while open
+    path: Option<PathBuf>,
+    /// By item id
+    uses: HashMap<String, Use>,
+}
+
+impl Frecency {
+    /// The uses of the items of the program
+    pub fn load(program_name: &str) -> Frecency {
+        match dirs::state_dir() {
+            Some(dir) => {
+                Frecency::open(dir.join("ilia").join(format!("{}.frecency", program_name)))
+            }
+            None => Frecency::default(),
+        }
+    }
+
+    /// The uses kept in the file, which is created on the first use if missing
+    pub fn open(path: PathBuf) -> Frecency {
+        let uses = std::fs::read_to_string(&path)
+            .map(|contents| contents.lines().filter_map(parse).collect())
+            .unwrap_or_default();
+        Frecency {
+            path: Some(path),
+            uses,
+        }
+    }
+
+    /// Count a use of the item now
+    pub fn record(&mut self, id: &str) -> anyhow::Result<()> {
+        // Lines are uses
+        if id.contains('\n') {
+            return Ok(());
+        }
+        self.record_at(id, now_secs());
+        self.save()
+    }
+
+    fn record_at(&mut self, id: &str, now: u64) {
+        let used = self
+            .uses
+            .entry(id.to_string())
+            .or_insert(Use { count: 0, last: 0 });
+        used.count += 1;
+        used.last = now;
+    }
+
+    /// How often the item was taken, however long ago.  0 if never.
+    pub fn count(&self, id: &str) -> u64 {
+        self.uses.get(id).map_or(0, |used| used.count)
+    }
+
+    /// How often the item was taken, the more recent uses counting more.  0 if never.
+    pub fn score(&self, id: &str) -> u64 {
+        self.score_at(id, now_secs())
+    }
+
+    fn score_at(&self, id: &str, now: u64) -> u64 {
+        let Some(used) = self.uses.get(id) else {
+            return 0;
+        };
+        let weight = match now.saturating_sub(used.last) / DAY_SECS {
+            0 => 8,
+            1..=6 => 4,
+            7..=29 => 2,
+            _ => 1,
+        };
+        used.count * weight
+    }
+
+    fn save(&self) -> anyhow::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).context("Failed to create state dir")?;
+        }
+        let contents: String = self
+            .uses
+            .iter()
+            .map(|(id, used)| format!("{} {} {}\n", used.count, used.last, id))
+            .collect();
+        std::fs::write(path, contents).context("Failed to save frecency")
+    }
+}
+
+// A line of the file: the count, the time of the last use and the id
+fn parse(line: &str) -> Option<(String, Use)> {
+    let mut fields = line.splitn(3, ' ');
+    let count = fields.next()?.parse().ok()?;
+    let last = fields.next()?.parse().ok()?;
+    let id = fields.next()?;
+    Some((id.to_string(), Use { count, last }))
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recent_uses_count_more() {
+        let now = 100 * DAY_SECS;
+        let mut frecency = Frecency::default();
+        for _ in 0..3 {
+            frecency.record_at("firefox.desktop", now - 40 * DAY_SECS);
+        }
+        frecency.record_at("org.gnome.Terminal.desktop", now);
+        assert_eq!(frecency.score_at("firefox.desktop", now), 3);
+        assert_eq!(frecency.score_at("org.gnome.Terminal.desktop", now), 8);
+        assert_eq!(frecency.score_at("code.desktop", now), 0);
+        assert_eq!(frecency.count("firefox.desktop"), 3);
+        assert_eq!(frecency.count("code.desktop"), 0);
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            parse("3 86400 My App.desktop"),
+            Some((
+                String::from("My App.desktop"),
+                Use {
+                    count: 3,
+                    last: 86400
+                }
+            ))
+        );
+        assert_eq!(parse("3 My App.desktop"), None);
+    }
+}
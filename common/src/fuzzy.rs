@@ -0,0 +1,227 @@
+//! fzf-style matching of the query against item titles: the query's characters must appear in
+//! order, with consecutive characters and word starts scoring higher, so "ffx" finds "Firefox"
+use std::borrow::Cow;
+use std::ops::Range;
+
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+// A matcher of its own for each match: SkimMatcherV2 reuses its score matrix without resetting
+// every cell it reads, so a shared one scores a title by the titles matched before it, and
+// turning its cache off panics
+fn matcher(case_sensitive: bool) -> SkimMatcherV2 {
+    if case_sensitive {
+        SkimMatcherV2::default().respect_case()
+    } else {
+        SkimMatcherV2::default().ignore_case()
+    }
+}
+
+/// Added to the score of a title starting with the query
+const PREFIX_BOOST: i64 = 64;
+/// Added to the score of a title with a word starting with the query
+const WORD_BOOST: i64 = 32;
+
+/// How well the title matches the query, higher being better, or None if it doesn't.  Titles
+/// with the query at their start, or else at the start of a word, rank higher.
+pub fn score(title: &str, query: &str, case_sensitive: bool) -> Option<i64> {
+    let (title, query) = (fold_text(title), fold_text(query));
+    let score = matcher(case_sensitive).fuzzy_match(&title, &query)?;
+    Some(score + boost(&title, &query, case_sensitive))
+}
+
+// How much the position of the query as one piece within the title adds to its score
+fn boost(title: &str, query: &str, case_sensitive: bool) -> i64 {
+    let (title, query) = if case_sensitive {
+        (Cow::Borrowed(title), Cow::Borrowed(query))
+    } else {
+        (
+            Cow::Owned(title.to_lowercase()),
+            Cow::Owned(query.to_lowercase()),
+        )
+    };
+    if query.is_empty() {
+        return 0;
+    }
+    if title.starts_with(query.as_ref()) {
+        return PREFIX_BOOST;
+    }
+    let at_word_start = title.match_indices(query.as_ref()).any(|(start, _)| {
+        title[..start]
+            .chars()
+            .next_back()
+            .is_some_and(|previous| !previous.is_alphanumeric())
+    });
+    if at_word_start {
+        WORD_BOOST
+    } else {
+        0
+    }
+}
+
+/// The byte ranges of the title matched by the query's characters, merged where they adjoin
+pub fn match_ranges(title: &str, query: &str, case_sensitive: bool) -> Vec<Range<usize>> {
+    if query.is_empty() {
+        return vec![];
+    }
+    let folded = fold(title);
+    let Some((_, indices)) = matcher(case_sensitive).fuzzy_indices(&folded.text, &fold_text(query))
+    else {
+        return vec![];
+    };
+
+    // The matcher reports character positions of the folded title
+    let mut ranges: Vec<Range<usize>> = vec![];
+    for index in indices {
+        let Some(origin) = folded.origins.get(index) else {
+            continue;
+        };
+        match ranges.last_mut() {
+            Some(last) if last.end >= origin.start => last.end = last.end.max(origin.end),
+            _ => ranges.push(origin.clone()),
+        }
+    }
+    ranges
+}
+
+/// Text with its diacritics removed, so "Éclair" is found by "eclair" however its "É" is encoded.
+/// Compatibility characters are replaced too, so the "ﬁ" ligature is found by "fi".
+struct Folded<'a> {
+    text: Cow<'a, str>,
+    /// The byte range of the original character each character of the text comes from
+    origins: Vec<Range<usize>>,
+}
+
+fn fold(text: &str) -> Folded<'_> {
+    let mut folded = String::with_capacity(text.len());
+    let mut origins = Vec::with_capacity(text.len());
+    for (start, c) in text.char_indices() {
+        let origin = start..start + c.len_utf8();
+        if c.is_ascii() {
+            folded.push(c);
+            origins.push(origin);
+            continue;
+        }
+        // A mark combining with the previous letter belongs to that letter's origin
+        if is_combining_mark(c) {
+            if let Some(previous) = origins.last_mut() {
+                previous.end = origin.end;
+            }
+            continue;
+        }
+        // Decompose into the base letters and their combining marks, dropping the marks
+        for base in std::iter::once(c)
+            .nfkd()
+            .filter(|decomposed| !is_combining_mark(*decomposed))
+        {
+            folded.push(base);
+            origins.push(origin.clone());
+        }
+    }
+    let text = if folded == text {
+        Cow::Borrowed(text)
+    } else {
+        Cow::Owned(folded)
+    };
+    Folded { text, origins }
+}
+
+// The text `fold` gives, without the origins only highlighting needs
+fn fold_text(text: &str) -> Cow<'_, str> {
+    if text.is_ascii() {
+        return Cow::Borrowed(text);
+    }
+    let folded: String = text
+        .chars()
+        .flat_map(|c| std::iter::once(c).nfkd())
+        .filter(|c| !is_combining_mark(*c))
+        .collect();
+    if folded == text {
+        Cow::Borrowed(text)
+    } else {
+        Cow::Owned(folded)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subsequence_matches() {
+        assert!(score("Firefox", "ffx", false).is_some());
+        assert!(score("Firefox", "xff", false).is_none());
+        assert!(score("Firefox", "FFX", true).is_none());
+    }
+
+    #[test]
+    fn test_contiguous_scores_higher() {
+        let contiguous = score("Files", "fil", false).unwrap();
+        let scattered = score("Profile Manager", "fil", false).unwrap();
+        assert!(contiguous > scattered);
+    }
+
+    #[test]
+    fn test_prefix_and_word_start_boosted() {
+        let prefix = score("Firefox", "fi", false).unwrap();
+        let word = score("Mozilla Firefox", "fi", false).unwrap();
+        let inside = score("Profile Manager", "fi", false).unwrap();
+        assert!(prefix > word);
+        assert!(word > inside);
+        assert!(score("Bonfire", "fire", false) < score("Mozilla Firefox", "fire", false));
+    }
+
+    #[test]
+    fn test_score_independent_of_previous_matches() {
+        let alone = score("daaCcB ", "ccb", false);
+        assert!(score("cACd b ", "ccb", false).is_some());
+        assert_eq!(score("daaCcB ", "ccb", false), alone);
+    }
+
+    #[test]
+    fn test_fold_text_matches_fold() {
+        for text in [
+            "Firefox",
+            "Éclair",
+            "Cafe\u{301}",
+            "Crème brûlée",
+            "\u{FB01}refox",
+        ] {
+            assert_eq!(fold_text(text), fold(text).text);
+        }
+    }
+
+    #[test]
+    fn test_diacritics_folded() {
+        assert!(score("Éclair", "eclair", false).is_some());
+        assert!(score("Größe", "grosse", false).is_none());
+        assert!(score("Grüne Äpfel", "grune apfel", false).is_some());
+        assert!(score("Eclair", "éclair", false).is_some());
+        // Composed and decomposed forms are equal
+        assert!(score("Cafe\u{301}", "café", false).is_some());
+        assert!(score("Café", "cafe\u{301}", false).is_some());
+        assert!(score("\u{FB01}refox", "fire", false).is_some());
+        assert_eq!(
+            match_ranges("Cafe\u{301}", "fé", false).first(),
+            Some(&(2..6))
+        );
+        // Highlights cover the accented characters of the original title
+        assert_eq!(match_ranges("Éclair", "ec", false).first(), Some(&(0..3)));
+        assert_eq!(
+            match_ranges("Crème brûlée", "brulee", false).first(),
+            Some(&(7..15))
+        );
+    }
+
+    #[test]
+    fn test_match_ranges() {
+        assert_eq!(match_ranges("Firefox", "ffx", false), [0..1, 4..5, 6..7]);
+        // Adjoining characters merge into one range, multibyte ones included
+        assert_eq!(match_ranges("Files", "fil", false).first(), Some(&(0..3)));
+        assert_eq!(match_ranges("Größe", "öß", false).first(), Some(&(2..6)));
+        assert_eq!(match_ranges("Gräben", "rä", false).len(), 1);
+        assert!(match_ranges("Files", "", false).is_empty());
+    }
+}
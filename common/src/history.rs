@@ -0,0 +1,99 @@
+//! Queries which took an item, kept per front-end in `$XDG_STATE_HOME/ilia` so they can be
+//! recalled like shell history
+use std::path::PathBuf;
+
+use anyhow::Context;
+
+#[derive(Debug)]
+pub struct History {
+    /// Where the queries are kept, or None to keep them only while open
+    path: Option<PathBuf>,
+    /// Oldest first
+    queries: Vec<String>,
+    /// The most queries kept
+    size: usize,
+}
+
+impl History {
+    /// A history kept only while open, of up to `size` queries
+    pub fn new(size: usize) -> History {
+        History {
+            path: None,
+            queries: vec![],
+            size,
+        }
+    }
+
+    /// The history of the program, keeping up to `size` queries
+    pub fn load(program_name: &str, size: usize) -> History {
+        let path = dirs::state_dir()
+            .map(|dir| dir.join("ilia").join(format!("{}.history", program_name)))
+            .filter(|_| size > 0);
+        let queries = path
+            .as_ref()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .map(|contents| contents.lines().map(String::from).collect())
+            .unwrap_or_default();
+        let mut history = History {
+            path,
+            queries,
+            size,
+        };
+        history.truncate();
+        history
+    }
+
+    /// The query taken `back` queries before the last one, the last one being 0
+    pub fn get(&self, back: usize) -> Option<&str> {
+        self.queries
+            .len()
+            .checked_sub(back + 1)
+            .map(|index| self.queries[index].as_str())
+    }
+
+    /// Add the query as the last one, moving it if it was taken before
+    pub fn push(&mut self, query: &str) -> anyhow::Result<()> {
+        // Lines are queries
+        if self.size == 0 || query.contains('\n') {
+            return Ok(());
+        }
+        self.queries.retain(|taken| taken != query);
+        self.queries.push(query.to_string());
+        self.truncate();
+        self.save()
+    }
+
+    fn truncate(&mut self) {
+        let excess = self.queries.len().saturating_sub(self.size);
+        self.queries.drain(..excess);
+    }
+
+    fn save(&self) -> anyhow::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).context("Failed to create state dir")?;
+        }
+        let mut contents = self.queries.join("\n");
+        contents.push('\n');
+        std::fs::write(path, contents).context("Failed to save history")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_push() {
+        let mut history = History::new(3);
+        for query in ["fire", "term", "files", "fire", "code"] {
+            history.push(query).unwrap();
+        }
+        assert_eq!(history.get(0), Some("code"));
+        assert_eq!(history.get(1), Some("fire"));
+        assert_eq!(history.get(2), Some("files"));
+        assert_eq!(history.get(3), None);
+    }
+}
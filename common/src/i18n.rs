@@ -0,0 +1,174 @@
+//! Translations of the launcher's own strings, as Fluent resources selected by the locale of
+//! the environment.  Item titles are the provider's concern.
+use std::sync::LazyLock;
+
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource};
+use unic_langid::{CharacterDirection, LanguageIdentifier};
+
+/// The locale used when the environment asks for none of the translated ones
+const FALLBACK_LOCALE: &str = "en-US";
+
+/// The translations bundled into the binary, by locale
+const RESOURCES: [(&str, &str); 2] = [
+    ("en-US", include_str!("../locales/en-US.ftl")),
+    ("de", include_str!("../locales/de.ftl")),
+];
+
+static REQUESTED: LazyLock<Vec<LanguageIdentifier>> = LazyLock::new(|| {
+    // Tests describe the UI in English regardless of the developer's locale
+    if cfg!(test) {
+        vec![]
+    } else {
+        requested_locales()
+    }
+});
+
+static BUNDLES: LazyLock<Vec<FluentBundle<FluentResource>>> = LazyLock::new(|| {
+    let locale = negotiate(&REQUESTED);
+    // Messages missing from a translation come from the fallback
+    [
+        locale,
+        FALLBACK_LOCALE.parse().expect("Invalid fallback locale"),
+    ]
+    .into_iter()
+    .map(bundle)
+    .collect()
+});
+
+/// Whether the preferred locale is written right to left, so the layout should be mirrored.
+/// Follows the environment even when there is no translation for the locale.
+pub fn is_rtl() -> bool {
+    REQUESTED
+        .first()
+        .is_some_and(|locale| locale.character_direction() == CharacterDirection::RTL)
+}
+
+/// Format a message of the current locale
+pub fn tr(id: &str) -> String {
+    format(id, None)
+}
+
+/// Format a message of the current locale, with named arguments
+pub fn tr_with(id: &str, args: &[(&str, &str)]) -> String {
+    let mut fluent_args = FluentArgs::new();
+    for (name, value) in args {
+        fluent_args.set(*name, *value);
+    }
+    format(id, Some(&fluent_args))
+}
+
+fn format(id: &str, args: Option<&FluentArgs>) -> String {
+    BUNDLES
+        .iter()
+        .find_map(|bundle| {
+            let pattern = bundle.get_message(id)?.value()?;
+            let mut errors = vec![];
+            Some(
+                bundle
+                    .format_pattern(pattern, args, &mut errors)
+                    .into_owned(),
+            )
+        })
+        // Show the id rather than nothing, so a missing message is easy to spot
+        .unwrap_or_else(|| id.to_string())
+}
+
+fn bundle(locale: LanguageIdentifier) -> FluentBundle<FluentResource> {
+    let (_, source) = RESOURCES
+        .iter()
+        .find(|(name, _)| {
+            name.parse::<LanguageIdentifier>()
+                .is_ok_and(|name| name == locale)
+        })
+        .expect("No resource for negotiated locale");
+    let resource = FluentResource::try_new(source.to_string()).expect("Invalid bundled resource");
+    let mut bundle = FluentBundle::new_concurrent(vec![locale]);
+    // Isolation marks keep arguments such as the query in order within right to left text, but
+    // are only stray characters in left to right labels
+    bundle.set_use_isolating(is_rtl());
+    bundle
+        .add_resource(resource)
+        .expect("Conflicting bundled messages");
+    bundle
+}
+
+// Read the preferred locales from the environment, most preferred first, as gettext does
+fn requested_locales() -> Vec<LanguageIdentifier> {
+    let language = std::env::var("LANGUAGE").unwrap_or_default();
+    let locale = ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .into_iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|value| !value.is_empty())
+        .unwrap_or_default();
+
+    language
+        .split(':')
+        .chain([locale.as_str()])
+        .filter_map(|value| {
+            // Such as "de_DE.UTF-8@euro", "C" and "POSIX" being no locale at all
+            let name = value.split(['.', '@']).next()?.replace('_', "-");
+            match name.as_str() {
+                "" | "C" | "POSIX" => None,
+                _ => name.parse().ok(),
+            }
+        })
+        .collect()
+}
+
+// Pick the first requested locale with a translation, matching on the language alone if needed
+fn negotiate(requested: &[LanguageIdentifier]) -> LanguageIdentifier {
+    let available: Vec<LanguageIdentifier> = RESOURCES
+        .iter()
+        .filter_map(|(name, _)| name.parse().ok())
+        .collect();
+    requested
+        .iter()
+        .find_map(|locale| {
+            available
+                .iter()
+                .find(|candidate| *candidate == locale)
+                .or_else(|| {
+                    available
+                        .iter()
+                        .find(|candidate| candidate.language == locale.language)
+                })
+        })
+        .cloned()
+        .unwrap_or_else(|| FALLBACK_LOCALE.parse().expect("Invalid fallback locale"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn locales(names: &[&str]) -> Vec<LanguageIdentifier> {
+        names.iter().map(|name| name.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_negotiate_by_language() {
+        assert_eq!(negotiate(&locales(&["de-AT"])), "de");
+        assert_eq!(negotiate(&locales(&["en-GB"])), "en-US");
+    }
+
+    #[test]
+    fn test_negotiate_in_preference_order() {
+        assert_eq!(negotiate(&locales(&["ja", "de-DE", "en-US"])), "de");
+    }
+
+    #[test]
+    fn test_negotiate_untranslated() {
+        assert_eq!(negotiate(&locales(&["ja"])), "en-US");
+        assert_eq!(negotiate(&[]), "en-US");
+    }
+
+    #[test]
+    fn test_format_with_args() {
+        assert_eq!(
+            tr_with("create-row", &[("query", "notes")]),
+            "Create 'notes'"
+        );
+        assert_eq!(tr("no-such-message"), "no-such-message");
+    }
+}
@@ -0,0 +1,52 @@
+//! Icons of items, looked up by name in the desktop's icon theme.  Lookups search many
+//! directories, so they run in the background and the list draws the icons found so far.
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+
+/// Size in pixels icons are looked up at and drawn
+pub const ICON_SIZE: u16 = 24;
+
+/// Where each icon name was found, or None if it wasn't
+static FOUND: LazyLock<Mutex<HashMap<String, Option<PathBuf>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// The icon theme of GTK, asked of gsettings once
+static GTK_THEME: LazyLock<Option<String>> = LazyLock::new(freedesktop_icons::default_theme_gtk);
+
+/// The file of the icon, given its name or path, if it is known without a lookup
+pub fn cached(icon: &str) -> Option<PathBuf> {
+    if Path::new(icon).is_absolute() {
+        return Some(PathBuf::from(icon));
+    }
+    FOUND
+        .lock()
+        .unwrap_or_else(|err| err.into_inner())
+        .get(icon)
+        .cloned()
+        .flatten()
+}
+
+/// Whether the icon needs a lookup before it is drawn
+pub fn is_unresolved(icon: &str) -> bool {
+    !Path::new(icon).is_absolute()
+        && !FOUND
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .contains_key(icon)
+}
+
+/// Look the icons up in the theme, or else the GTK theme, so they are `cached`
+pub fn resolve(icons: &[String], theme: Option<&str>) {
+    let theme = theme.or(GTK_THEME.as_deref()).unwrap_or("hicolor");
+    for icon in icons {
+        let path = freedesktop_icons::lookup(icon)
+            .with_size(ICON_SIZE)
+            .with_theme(theme)
+            .find();
+        FOUND
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .insert(icon.clone(), path);
+    }
+}
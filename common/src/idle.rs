@@ -0,0 +1,123 @@
+//! Keeping the screen from idling while the launcher is open, with the Wayland idle-inhibit
+//! protocol.  The inhibitor must be made for the launcher's own surface, so it is requested over
+//! the connection iced already has open.
+use std::ffi::c_void;
+use std::fmt::Debug;
+
+use anyhow::Context;
+use iced_runtime::window::raw_window_handle::{RawWindowHandle, WindowHandle};
+use wayland_client::backend::{Backend, ObjectId};
+use wayland_client::globals::{registry_queue_init, GlobalListContents};
+use wayland_client::protocol::{wl_registry, wl_surface::WlSurface};
+use wayland_client::{Connection, Dispatch, EventQueue, Proxy, QueueHandle};
+use wayland_protocols::wp::idle_inhibit::zv1::client::zwp_idle_inhibit_manager_v1::ZwpIdleInhibitManagerV1;
+use wayland_protocols::wp::idle_inhibit::zv1::client::zwp_idle_inhibitor_v1::ZwpIdleInhibitorV1;
+
+/// Keeps the screen from idling as long as it is held
+pub struct IdleInhibitor {
+    inhibitor: ZwpIdleInhibitorV1,
+    connection: Connection,
+    _queue: EventQueue<Inhibit>,
+}
+
+impl Debug for IdleInhibitor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IdleInhibitor")
+            .field("inhibitor", &self.inhibitor.id())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for IdleInhibitor {
+    fn drop(&mut self) {
+        self.inhibitor.destroy();
+        let _ = self.connection.flush();
+    }
+}
+
+/// Inhibit idling while the window is visible
+pub fn inhibit(window: WindowHandle<'_>) -> anyhow::Result<IdleInhibitor> {
+    let RawWindowHandle::Wayland(handle) = window.as_raw() else {
+        anyhow::bail!("Idle inhibition needs a Wayland window");
+    };
+    let surface = handle.surface.as_ptr();
+
+    // SAFETY: the surface belongs to the window, which lives as long as the app, and so does the
+    // display it was made on
+    let connection = Connection::from_backend(unsafe {
+        Backend::from_foreign_display(proxy_display(surface)?.cast())
+    });
+    let surface = WlSurface::from_id(&connection, unsafe {
+        ObjectId::from_ptr(WlSurface::interface(), surface.cast())
+    }?)?;
+
+    let (globals, mut queue) =
+        registry_queue_init::<Inhibit>(&connection).context("Failed to list globals")?;
+    let manager: ZwpIdleInhibitManagerV1 = globals
+        .bind(&queue.handle(), 1..=1, ())
+        .context("Compositor does not support idle inhibition")?;
+    let inhibitor = manager.create_inhibitor(&surface, &queue.handle(), ());
+    manager.destroy();
+    queue
+        .roundtrip(&mut Inhibit)
+        .context("Failed to inhibit idling")?;
+
+    Ok(IdleInhibitor {
+        inhibitor,
+        connection,
+        _queue: queue,
+    })
+}
+
+// The display a proxy was made on.  Looked up at run time, as libwayland only has it from 1.23.
+fn proxy_display(proxy: *mut c_void) -> anyhow::Result<*mut c_void> {
+    // SAFETY: loading libwayland, which is already loaded for the window, runs no initializers
+    // and the signature is that of wl_proxy_get_display
+    unsafe {
+        let library = libloading::Library::new("libwayland-client.so.0")
+            .context("Unable to load libwayland-client")?;
+        let get_display = library
+            .get::<unsafe extern "C" fn(*mut c_void) -> *mut c_void>(b"wl_proxy_get_display")
+            .context("libwayland-client is older than 1.23")?;
+        Ok(get_display(proxy))
+    }
+}
+
+/// Dispatch state for the inhibitor's queue, on which no events arrive
+struct Inhibit;
+
+impl Dispatch<wl_registry::WlRegistry, GlobalListContents> for Inhibit {
+    fn event(
+        _: &mut Self,
+        _: &wl_registry::WlRegistry,
+        _: wl_registry::Event,
+        _: &GlobalListContents,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<ZwpIdleInhibitManagerV1, ()> for Inhibit {
+    fn event(
+        _: &mut Self,
+        _: &ZwpIdleInhibitManagerV1,
+        _: <ZwpIdleInhibitManagerV1 as Proxy>::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<ZwpIdleInhibitorV1, ()> for Inhibit {
+    fn event(
+        _: &mut Self,
+        _: &ZwpIdleInhibitorV1,
+        _: <ZwpIdleInhibitorV1 as Proxy>::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
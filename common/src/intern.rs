@@ -0,0 +1,65 @@
+//! Shared strings for item titles and commands, so reloading a model reuses the strings of the
+//! previous one rather than allocating them again, and clones of items stay cheap
+use std::collections::HashSet;
+use std::sync::{Arc, LazyLock, Mutex};
+
+/// Interned strings are pruned once there are this many, or twice as many as survived the last
+/// pruning, so titles of closed windows don't accumulate in a long running process
+const MIN_PRUNE_LEN: usize = 1024;
+
+#[derive(Debug)]
+struct Interner {
+    strings: HashSet<Arc<str>>,
+    prune_at: usize,
+}
+
+static INTERNER: LazyLock<Mutex<Interner>> = LazyLock::new(|| {
+    Mutex::new(Interner {
+        strings: HashSet::new(),
+        prune_at: MIN_PRUNE_LEN,
+    })
+});
+
+/// Return the shared copy of a string, allocating one only if no live copy exists
+pub fn intern(string: &str) -> Arc<str> {
+    let mut interner = INTERNER.lock().unwrap_or_else(|err| err.into_inner());
+    if let Some(interned) = interner.strings.get(string) {
+        return interned.clone();
+    }
+
+    if interner.strings.len() >= interner.prune_at {
+        // Only the interner holds the strings no item refers to anymore
+        interner
+            .strings
+            .retain(|interned| Arc::strong_count(interned) > 1);
+        interner.prune_at = (interner.strings.len() * 2).max(MIN_PRUNE_LEN);
+    }
+
+    let interned: Arc<str> = Arc::from(string);
+    interner.strings.insert(interned.clone());
+    interned
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_intern_shares_equal_strings() {
+        let first = intern("Firefox");
+        let second = intern(&String::from("Firefox"));
+        assert!(Arc::ptr_eq(&first, &second));
+        assert!(!Arc::ptr_eq(&first, &intern("Files")));
+    }
+
+    #[test]
+    fn test_intern_prunes_unused_strings() {
+        let kept = intern("kept");
+        for index in 0..MIN_PRUNE_LEN * 2 {
+            intern(&format!("closed window {}", index));
+        }
+        let interner = INTERNER.lock().unwrap();
+        assert!(interner.strings.len() <= MIN_PRUNE_LEN + 1);
+        assert!(interner.strings.contains(&kept));
+    }
+}
@@ -0,0 +1,269 @@
+//! Starting apps from the launcher as if from the session, configured by `Config::sanitize_launch`
+use std::ffi::OsStr;
+use std::os::unix::process::CommandExt;
+use std::process::Command;
+use std::sync::OnceLock;
+
+use anyhow::bail;
+
+use crate::Config;
+
+/// Longest argument Linux passes to a program (`MAX_ARG_STRLEN`), in bytes
+const MAX_ARG_BYTES: usize = 128 * 1024;
+/// Longest command line started, in bytes, well under what Linux passes along with the
+/// environment
+const MAX_COMMAND_BYTES: usize = 512 * 1024;
+
+/// Variables set by ilia or its toolkit for its own use, which launched apps shouldn't inherit
+const LAUNCHER_VARIABLES: [&str; 6] = [
+    "RUST_BACKTRACE",
+    "RUST_LOG",
+    "ICED_*",
+    "WGPU_*",
+    "WINIT_*",
+    "CARGO_*",
+];
+
+/// How apps are started, set once when the launcher starts
+static SANITIZE: OnceLock<Option<Sanitize>> = OnceLock::new();
+
+/// The front-end and the patterns of the command lines it may run, set once when the launcher
+/// starts if `Config::allowed_commands` restricts it
+static ALLOWED: OnceLock<Option<(String, Vec<String>)>> = OnceLock::new();
+
+#[derive(Debug)]
+struct Sanitize {
+    /// Patterns of the variables which are removed
+    remove: Vec<String>,
+    /// Patterns of the variables which are kept, even when removed by `remove`
+    keep: Vec<String>,
+}
+
+/// Take the launch settings of the configuration for the program name, for every later
+/// `command` and `check_allowed`
+pub(crate) fn configure(program_name: &str, config: &Config) {
+    let _ = ALLOWED.set(
+        config
+            .allowed_commands
+            .get(program_name)
+            .map(|patterns| (program_name.to_string(), patterns.clone())),
+    );
+    let _ = SANITIZE.set(config.sanitize_launch.then(|| {
+        Sanitize {
+            remove: LAUNCHER_VARIABLES
+                .iter()
+                .map(|pattern| pattern.to_string())
+                .chain(config.launch_env_remove.iter().cloned())
+                .collect(),
+            keep: config.launch_env_keep.clone(),
+        }
+    }));
+}
+
+/// A command which starts an app.  With `Config::sanitize_launch`, the app doesn't inherit the
+/// launcher's own variables, blocked signals, niceness or session.
+pub fn command(program: impl AsRef<OsStr>) -> Command {
+    let mut command = Command::new(program);
+    let Some(Some(sanitize)) = SANITIZE.get() else {
+        return command;
+    };
+
+    for (name, _) in std::env::vars_os() {
+        let Some(name) = name.to_str() else {
+            continue;
+        };
+        if matches_any(&sanitize.remove, name) && !matches_any(&sanitize.keep, name) {
+            command.env_remove(name);
+        }
+    }
+    // SAFETY: only async-signal-safe calls are made between fork and exec
+    unsafe {
+        command.pre_exec(|| {
+            let mut signals = std::mem::zeroed::<libc::sigset_t>();
+            libc::sigemptyset(&mut signals);
+            libc::sigprocmask(libc::SIG_SETMASK, &signals, std::ptr::null_mut());
+            // Lowering a raised niceness needs privileges, so this only undoes a negative one
+            libc::setpriority(libc::PRIO_PROCESS, 0, 0);
+            // Out of the launcher's session, so nothing sent to it reaches the app
+            libc::setsid();
+            Ok(())
+        });
+    }
+    command
+}
+
+/// Fail unless `Config::allowed_commands` lets the front-end run the command line, or print it
+pub fn check_allowed(command_line: &str) -> anyhow::Result<()> {
+    match ALLOWED.get() {
+        Some(Some((program_name, patterns))) if !allows(patterns, command_line) => bail!(
+            "{} may not run {:?}, as allowed_commands doesn't match it",
+            program_name,
+            command_line
+        ),
+        _ => Ok(()),
+    }
+}
+
+// Whether any of the glob patterns matches the whole command line
+fn allows(patterns: &[String], command_line: &str) -> bool {
+    patterns
+        .iter()
+        .any(|pattern| glob_matches(pattern, command_line))
+}
+
+// Whether the text matches the pattern, in which `*` matches any text and `?` one character.  A
+// mismatch after a `*` only retries from the last `*`, matching one more character with it, as
+// the earlier ones can't do better: the time is at most the product of the lengths.
+fn glob_matches(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Where the pattern continues after the last `*`, and where in the text it was tried
+    let mut retry: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                p += 1;
+                retry = Some((p, t));
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match retry {
+                Some((star_p, star_t)) => {
+                    p = star_p;
+                    t = star_t + 1;
+                    retry = Some((star_p, t));
+                }
+                None => return false,
+            },
+        }
+    }
+    // Only `*`s may be left of the pattern, matching nothing
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Fail with why the command line can't be started, such as from a malformed `Exec` key: it must
+/// name a program, and its arguments must be printable and of a size Linux passes along
+pub fn check_args<S: AsRef<str>>(args: &[S]) -> anyhow::Result<()> {
+    let Some(program) = args.first() else {
+        bail!("The command line is empty");
+    };
+    if program.as_ref().trim().is_empty() {
+        bail!("The command line names no program");
+    }
+    let mut total = 0;
+    for arg in args {
+        let arg = arg.as_ref();
+        if let Some(c) = arg.chars().find(|c| c.is_control()) {
+            bail!("The command line holds the unprintable character {:?}", c);
+        }
+        if arg.len() > MAX_ARG_BYTES {
+            bail!(
+                "An argument of the command line is {} bytes, over {}",
+                arg.len(),
+                MAX_ARG_BYTES
+            );
+        }
+        total += arg.len() + 1;
+    }
+    if total > MAX_COMMAND_BYTES {
+        bail!(
+            "The command line is {} bytes, over {}",
+            total,
+            MAX_COMMAND_BYTES
+        );
+    }
+    Ok(())
+}
+
+// Whether the name is one of the patterns, which may end in `*` to match any suffix
+fn matches_any(patterns: &[String], name: &str) -> bool {
+    patterns
+        .iter()
+        .any(|pattern| match pattern.strip_suffix('*') {
+            Some(prefix) => name.starts_with(prefix),
+            None => name == pattern,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_any() {
+        let patterns = [String::from("WINIT_*"), String::from("RUST_LOG")];
+        assert!(matches_any(&patterns, "WINIT_UNIX_BACKEND"));
+        assert!(matches_any(&patterns, "RUST_LOG"));
+        assert!(!matches_any(&patterns, "RUST_LOG_STYLE"));
+        assert!(!matches_any(&patterns, "WAYLAND_DISPLAY"));
+    }
+
+    #[test]
+    fn test_check_args() {
+        assert!(check_args(&["firefox", "--new-window"]).is_ok());
+        assert!(check_args::<&str>(&[]).is_err());
+        assert!(check_args(&["", "--new-window"]).is_err());
+        assert!(check_args(&["firefox", "a\nb"]).is_err());
+        assert!(check_args(&[String::from("echo"), "x".repeat(MAX_ARG_BYTES + 1)]).is_err());
+        let long = "x".repeat(MAX_ARG_BYTES);
+        let long = long.as_str();
+        assert!(check_args(&["echo", long, long, long, long]).is_err());
+    }
+
+    #[test]
+    fn test_allows() {
+        let patterns = [String::from("/opt/kiosk/*"), String::from("firefox ?U")];
+        assert!(allows(&patterns, "/opt/kiosk/reboot.sh --now"));
+        assert!(allows(&patterns, "firefox %U"));
+        assert!(!allows(&patterns, "firefox"));
+        assert!(!allows(&patterns, "sh -c /opt/kiosk/reboot.sh"));
+        assert!(!allows(&[], "true"));
+    }
+
+    #[test]
+    fn test_glob_matches() {
+        for (pattern, text) in [
+            ("", ""),
+            ("*", ""),
+            ("**", "abc"),
+            ("a*", "a"),
+            ("*c", "abc"),
+            ("a*b*c", "axxbyyc"),
+            ("a?c", "abc"),
+            ("*?", "x"),
+            ("a*bc", "abcbc"),
+            // `?` is one character, however many bytes it takes
+            ("caf?", "café"),
+            ("?*", "é"),
+        ] {
+            assert!(glob_matches(pattern, text), "{pattern} {text}");
+        }
+        for (pattern, text) in [
+            ("", "a"),
+            ("a", ""),
+            ("?", ""),
+            ("a*b", "acbc"),
+            ("*?", ""),
+            ("a?c", "ac"),
+            ("caf??", "café"),
+        ] {
+            assert!(!glob_matches(pattern, text), "{pattern} {text}");
+        }
+    }
+
+    #[test]
+    fn test_glob_matches_long_text() {
+        // A megabyte line, deeper than a recursive match could go
+        let line = "a".repeat(1 << 20);
+        assert!(glob_matches("*", &line));
+        assert!(glob_matches("a*a?", &line));
+        assert!(!glob_matches("*b", &line));
+        // Backtracking over every `*` would take exponential time
+        let line = "a".repeat(10_000);
+        assert!(!glob_matches("*a*a*a*a*a*a*b", &line));
+        assert!(glob_matches("*a*a*a*a*a*a*", &line));
+    }
+}
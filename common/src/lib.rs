@@ -1,17 +1,101 @@
+mod args;
+mod clipboard;
+mod config;
+mod crash;
+mod favorites;
+mod frecency;
+mod fuzzy;
+mod history;
+mod i18n;
+mod icon;
+mod idle;
+mod intern;
+mod launch;
+mod matcher;
+mod palette;
+mod profile;
+mod query;
+mod row;
+mod session;
+#[cfg(test)]
+mod testing;
+mod timeout;
+mod view;
+
+pub use args::CommonArgs;
+pub use clipboard::{copy_secret_to_clipboard, copy_to_clipboard};
+pub use config::{Config, CONFIG_HELP};
+pub use i18n::{is_rtl, tr, tr_with};
+pub use idle::IdleInhibitor;
+pub use intern::intern;
+pub use launch::{check_allowed, check_args, command};
+pub use matcher::{FuzzyMatcher, Matcher, RegexMatcher, SubstringMatcher, REGEX_PREFIX};
+pub use palette::PALETTE_PREFIX;
+use palette::{PaletteAction, Run};
+pub use profile::profile_startup;
+pub use query::EXCLUDE_PREFIX;
+pub use row::{Ellipsis, ItemRow};
+pub use timeout::Timeout;
+pub use view::{CountdownView, EntryView, RowView, ViewTree};
+
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::cmp::Reverse;
+use std::fmt::Debug;
+use std::ops::Range;
+use std::path::PathBuf;
 use std::process::exit;
-use std::sync::LazyLock;
+use std::rc::Rc;
+use std::sync::{Arc, LazyLock};
+use std::time::{Duration, Instant};
 
-use iced::widget::button::{primary, text};
-use iced::widget::scrollable::{snap_to, RelativeOffset};
-use iced::widget::{button, column, scrollable, text_input, Column};
+use anyhow::Context;
+use iced::alignment::Horizontal;
+use iced::application::{Appearance, DefaultStyle};
+use iced::mouse::ScrollDelta;
+use iced::widget::scrollable::{scroll_to, AbsoluteOffset, Viewport};
+use iced::widget::{
+    center, container, horizontal_rule, mouse_area, opaque, progress_bar, scrollable, text_input,
+    Column, Row, Space, Stack, Text,
+};
 use iced::window::settings::PlatformSpecific;
-use iced::{event, window, Element, Event, Font, Length, Pixels, Settings, Size, Task};
-use iced_core::keyboard::key::Named;
-use iced_core::keyboard::Key;
+use iced::window::Mode;
+use iced::{
+    event, window, Color, Element, Event, Font, Length, Padding, Pixels, Point, Settings, Size,
+    Task, Theme,
+};
+use iced_core::keyboard::key::{Code, Named, Physical};
+use iced_core::keyboard::{Key, Location, Modifiers};
+use iced_runtime::futures::futures::stream::BoxStream;
+use iced_runtime::futures::futures::{stream, StreamExt};
 use iced_runtime::futures::MaybeSend;
+use iced_runtime::task::Handle;
 
-/// A magic value to calculate relative pixel hight to move one item in the scrollable
-const ITEM_HEIGHT_SCALE_FACTOR: f32 = 0.00750;
+/// Approximate width in pixels of a character of row text, to fit titles to the list width
+const AVERAGE_GLYPH_WIDTH: f32 = 8.0;
+/// Approximate width in pixels of a character of the match counter, to keep typed text clear of it
+const COUNTER_GLYPH_WIDTH: f32 = 7.0;
+/// Space in pixels between the match counter and the edges of its badge
+const COUNTER_PADDING: f32 = 6.0;
+/// Size of the launcher window, or of the panel on the backdrop
+const WINDOW_SIZE: Size = Size {
+    width: 320.0,
+    height: 200.0,
+};
+/// Height of the query entry, which the window sheds when it is hidden
+const ENTRY_HEIGHT: f32 = 31.0;
+/// Width of a cell of the grid, and so of the window per column
+const GRID_CELL_WIDTH: f32 = 120.0;
+/// Lines of the list drawn beyond each edge of its view, so scrolling shows no gap before the
+/// list is drawn again
+const OVERSCAN_LINES: usize = 5;
+/// Rows from the top of the view which Alt and a digit take
+const SHORTCUT_ROWS: usize = 9;
+/// Pixels of smooth scrolling, as from a touchpad, which step the selection as a wheel click does
+const WHEEL_STEP_PIXELS: f32 = 20.0;
+
+/// Process exit code when the user dismisses the launcher without a selection, as rofi and dmenu do
+pub const EXIT_CANCELLED: i32 = 1;
 
 static ENTRY_WIDGET_ID: LazyLock<iced::widget::text_input::Id> =
     std::sync::LazyLock::new(|| iced::widget::text_input::Id::new("entry"));
@@ -21,6 +105,170 @@ static ITEMS_WIDGET_ID: LazyLock<iced::widget::scrollable::Id> =
 pub trait ItemDescriptor {
     fn title(&self) -> &str;
     fn exec(&self) -> anyhow::Result<()>;
+
+    /// What the item stands for, the same across reloads, such as a desktop entry's app id, so
+    /// the selection stays on it when items are reloaded or reordered.  The title by default.
+    fn id(&self) -> Cow<'_, str> {
+        Cow::Borrowed(self.title())
+    }
+
+    /// A secondary line drawn under the title in smaller, dimmer text, such as a description.
+    /// None by default.
+    fn subtitle(&self) -> Option<&str> {
+        None
+    }
+
+    /// A short tag drawn at the end of the row.  None by default.
+    fn badge(&self) -> Option<&str> {
+        None
+    }
+
+    /// An icon drawn before the title, by icon theme name such as "firefox" or by absolute
+    /// path.  None by default.
+    fn icon(&self) -> Option<&str> {
+        None
+    }
+
+    /// Further words the item is found by, such as "browser" for Firefox.  None by default.
+    fn keywords(&self) -> Option<&str> {
+        None
+    }
+
+    /// A description the item is found by.  None by default.
+    fn comment(&self) -> Option<&str> {
+        None
+    }
+
+    /// The command the item runs, found by name.  None by default.
+    fn exec_hint(&self) -> Option<&str> {
+        None
+    }
+
+    /// A question asked in place of the list before `exec` takes the item, such as whether to
+    /// end a process, answered with Enter or Escape.  None by default, taking it at once.
+    fn confirmation(&self) -> Option<String> {
+        None
+    }
+
+    /// The section the item is listed in, such as "Favorites", headed by its name where the list
+    /// moves into it.  Providers list the items of a section together.  None by default.
+    fn group(&self) -> Option<&str> {
+        None
+    }
+
+    /// Facts about the item by label, such as the command a desktop entry runs, shown under the
+    /// list while it is selected and details are toggled on.  None by default.
+    fn details(&self) -> Vec<(&'static str, String)> {
+        vec![]
+    }
+}
+
+/// The function performing an [`Action`] on an item
+pub type ActionFn<T> = Arc<dyn Fn(&T) -> anyhow::Result<()> + Send + Sync>;
+
+/// Complete, updated lists of items pushed by a provider while the launcher is open
+pub type ItemStream<T> = BoxStream<'static, Vec<T>>;
+
+/// Batches of items produced by a provider which loads incrementally, or why loading stopped
+pub type ItemBatches<T> = BoxStream<'static, anyhow::Result<Vec<T>>>;
+
+/// A secondary operation supported by an item, in addition to its primary `exec`
+#[derive(Clone)]
+pub struct Action<T> {
+    /// Stable identifier used to discover a capability across modes, such as "kill" or "copy"
+    pub id: &'static str,
+    /// Human readable name of the action
+    pub name: String,
+    /// Perform the action on an item
+    pub run: ActionFn<T>,
+}
+
+impl<T> Debug for Action<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Action")
+            .field("id", &self.id)
+            .field("name", &self.name)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A source of items, such as desktop entries or open windows, shared between front-ends
+pub trait ItemProvider: Debug + Send + Sync {
+    type Item: ItemDescriptor;
+
+    /// Produce the complete list of items offered by this provider
+    fn load(&self) -> anyhow::Result<Vec<Self::Item>>;
+
+    /// Produce the items in batches as they become available, for providers whose items arrive
+    /// slowly, such as through a pipe, so the first are listed while the rest load.  Each batch
+    /// is appended to the items before it.  None by default, loading the items with `load`.
+    fn load_incremental(&self) -> Option<ItemBatches<Self::Item>> {
+        None
+    }
+
+    /// Why some of the items last loaded are missing, such as one of several sources failing,
+    /// shown in a banner above the others.  Asked once they are listed, None by default.
+    fn load_warning(&self) -> Option<String> {
+        None
+    }
+
+    /// Produce an up to date list of items, given the currently loaded ones.  Reloads by default.
+    fn refresh(&self, _current: &[Self::Item]) -> anyhow::Result<Vec<Self::Item>> {
+        self.load()
+    }
+
+    /// Whether the provider matches items itself, receiving each query through `query`
+    /// instead of enumerating everything up front for the built-in filter
+    fn is_queryable(&self) -> bool {
+        false
+    }
+
+    /// Produce the items matching a query, for providers which are `is_queryable`
+    fn query(&self, _query: &str) -> anyhow::Result<Vec<Self::Item>> {
+        self.load()
+    }
+
+    /// Late-arriving data for an item, such as a resolved icon or thumbnail.  Nothing by default.
+    fn enrich(&self, _item: &Self::Item) -> Option<Self::Item> {
+        None
+    }
+
+    /// An item which creates something named by the query, such as a workspace or a note, offered
+    /// in a row pinned below the results.  None by default.
+    fn create(&self, _query: &str) -> Option<Self::Item> {
+        None
+    }
+
+    /// The item taking the query as typed when it is submitted rather than an item, such as a
+    /// command to run or a workspace to open.  None by default.
+    fn submit(&self, _query: &str) -> Option<Self::Item> {
+        None
+    }
+
+    /// Updates to the items that arrive while the launcher is open, such as items sent by other
+    /// processes.  Called on every update of the UI but only polled once, so any work belongs in
+    /// the stream.  None by default.
+    fn watch(&self) -> Option<ItemStream<Self::Item>> {
+        None
+    }
+
+    /// The secondary actions an item supports, listed first in the command palette when it
+    /// opens on the item, as Ctrl+Enter opens it.  None by default.
+    fn actions(&self, _item: &Self::Item) -> Vec<Action<Self::Item>> {
+        vec![]
+    }
+
+    /// Whether several items can be marked with Ctrl+Space and taken together by one Enter
+    /// through `exec_marked`.  Not by default.
+    fn marks(&self) -> bool {
+        false
+    }
+
+    /// Take the marked items at once, such as with a single IPC command.  Takes each in turn by
+    /// default.
+    fn exec_marked(&self, items: &[Self::Item]) -> anyhow::Result<()> {
+        items.iter().try_for_each(|item| item.exec())
+    }
 }
 
 /// The application model type.  See [the iced book](https://book.iced.rs/) for details.
@@ -34,98 +282,747 @@ pub struct State<T: MaybeSend + ItemDescriptor> {
     selected_index: usize,
     /// A flag to indicate app window has received focus. Work around to some windowing environments passing `unfocused` unexpectedly.
     received_focus: bool,
+    /// When the app started, to ignore focus loss during the configured grace period
+    started: Instant,
+    /// When the timeout runs out, unless a key press stopped it
+    deadline: Option<Instant>,
+    /// The theme the UI is drawn with
+    theme: Theme,
+    /// Whether the filter distinguishes upper and lower case
+    case_sensitive: bool,
+    /// Whether the details of the selected item are shown under the list
+    details: bool,
+    /// A flag to indicate a reload is in progress and `apps` is stale until the next `ModelLoaded`
+    reloading: bool,
+    /// A flag to indicate the first load is in progress, so the empty list is not mistaken for
+    /// nothing to list
+    loading: bool,
+    /// The in-flight enrichments of `apps`, one per batch loaded, aborted when dropped along with
+    /// the model they index
+    enrichment: Vec<Handle>,
+    /// The in-flight query of a queryable provider, aborted when dropped as it is superseded
+    pending_query: Option<Handle>,
+    /// The row whose primary action is running, and the handle to cancel it
+    busy: Option<(usize, Handle)>,
+    /// A failure to show in a banner above the list
+    error: Option<String>,
+    /// The modifier keys currently held
+    modifiers: Modifiers,
+    /// The modifier keys of the binding which launched, held since startup, whose release takes
+    /// the selected item when releasing executes
+    launch_modifiers: Modifiers,
+    /// The visible part of the list as last drawn, once it overflows
+    viewport: Option<Viewport>,
+    /// Height of one list row in pixels, measured from the drawn list
+    row_height: Option<f32>,
+    /// Keeps the screen from idling while the launcher is open, released when dropped
+    idle_inhibitor: Option<Arc<IdleInhibitor>>,
+    /// Matches regular expression queries, keeping the last pattern compiled
+    regex: RegexMatcher,
+    /// Whether the query entry is hidden, leaving only the list
+    entry_hidden: bool,
+    /// Where titles too long for their row are cut
+    ellipsis: Ellipsis,
+    /// The queries which took an item before
+    history: history::History,
+    /// How far back in the history the recalled query in the entry is, if it is one
+    history_index: Option<usize>,
+    /// How often and recently each item was taken, for the frecency order
+    frecency: frecency::Frecency,
+    /// The items pinned to lead the list for the empty query
+    favorites: favorites::Favorites,
+    /// The items matched by the last fuzzy query, cleared whenever `apps` changes
+    filter_cache: RefCell<Option<FilterCache>>,
+    /// Indices into `apps` of the items matching the query, best match first, matched again
+    /// once the query, the items or their order change
+    matches: RefCell<Option<Rc<[usize]>>>,
+    /// The item to take once its confirmation is answered
+    confirming: Option<T>,
+    /// The item selected as the palette opened, and the actions it supports
+    acting_on: Option<(T, Vec<Action<T>>)>,
+    /// Ids of the items marked to be taken together, in the order they were marked
+    marked: Vec<String>,
+    /// Where the pointer last moved over the list, to tell its moves from the list moving under it
+    pointer: Option<Point>,
+    /// Whether hovering a row selects it, from when the pointer moves until a key is pressed
+    hover_selects: bool,
+    /// Scrolling not yet enough for a step of the selection, in wheel clicks, down being positive
+    wheel: f32,
 }
 
-/// Root struct of application
+/// The items a fuzzy query matched, among which any longer query starting with it is found
 #[derive(Debug)]
+struct FilterCache {
+    query: String,
+    case_sensitive: bool,
+    /// Indices into `apps` of the matched items, in load order
+    indices: Vec<usize>,
+}
+
+/// Root struct of application
 pub struct Ilia<T: MaybeSend + ItemDescriptor> {
     state: State<T>,
     flags: IliaConfiguration<T>,
 }
 
+impl<T: MaybeSend + ItemDescriptor + Debug> Debug for Ilia<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut ilia = f.debug_struct("Ilia");
+        // The state holds the entry, which is a secret when obscured
+        if self.flags.obscured {
+            ilia.field("state", &"<obscured>");
+        } else {
+            ilia.field("state", &self.state);
+        }
+        ilia.field("flags", &self.flags).finish()
+    }
+}
+
 /// Messages are how your logic mutates the app state and GUI
 #[derive(Debug, Clone)]
 pub enum IliaMessage<T: MaybeSend> {
     /// Signals that the `ItemDescriptor` have been fully loaded into the vec
     ModelLoaded(Vec<T>),
+    /// Signals that another batch of a provider loading incrementally is to follow the items
+    ModelAppended(Vec<T>),
+    /// Signals that the provider could not produce the items, and why
+    LoadFailed(String),
+    /// Signals that a queryable provider has produced the items for the query
+    QueryLoaded(String, Vec<T>),
+    /// Signals that late-arriving data is available for the item at the index of the loaded vec
+    ItemUpdated(usize, T),
     /// Signals that the primary text edit box on the UI has been changed by the user, including the new text.
     EntryUpdate(String),
     /// Signals that the user has taken primary action on a selection.
     ExecuteSelected(),
-    /// Signals that the user has pressed a key
-    KeyEvent(Key),
+    /// Signals that the user has submitted the typed text rather than an item: Enter with no
+    /// item to select, or Shift+Enter whatever is selected
+    SubmitQuery(String),
+    /// Signals that the primary action on a selection has completed, or the reason it failed
+    ExecuteFinished(Result<(), String>),
+    /// Signals that the user has pressed a key, with the modifiers held at the time
+    KeyEvent(Key, Modifiers),
+    /// Signals that the set of held modifier keys has changed
+    ModifiersChanged(Modifiers),
+    /// Signals that the list has been scrolled or laid out again
+    Scrolled(Viewport),
     /// Signals that the window has gained focus
     GainedFocus,
     /// Signals that the window has lost focus
     LostFocus,
+    /// Signals that the user has clicked the row at the index
+    RowClicked(usize),
+    /// Signals that the pointer is over the row at the index
+    RowHovered(usize),
+    /// Signals that the mouse wheel or touchpad has scrolled over the list, when it steps the
+    /// selection
+    WheelScrolled(ScrollDelta),
+    /// Signals that the pointer has moved over the list, to where within it
+    PointerMoved(Point),
+    /// Signals that the user has clicked the backdrop outside the launcher panel
+    Dismissed,
+    /// Signals that the time left before the timeout should be checked
+    TimeoutTicked,
+    /// Signals that the icons of items have been looked up, so they can be drawn
+    IconsResolved,
+    /// Signals that idling is inhibited while the inhibitor is held, or why it is not
+    IdleInhibited(Result<Arc<IdleInhibitor>, String>),
+}
+
+impl<T: MaybeSend> IliaMessage<T> {
+    /// The message reporting the outcome of loading the items
+    fn from_load(result: anyhow::Result<Vec<T>>) -> Self {
+        match result {
+            Ok(items) => IliaMessage::ModelLoaded(items),
+            Err(err) => IliaMessage::LoadFailed(format!("{:#}", err)),
+        }
+    }
+
+    /// The message reporting a batch of items, or why no more are coming
+    fn from_batch(result: anyhow::Result<Vec<T>>) -> Self {
+        match result {
+            Ok(items) => IliaMessage::ModelAppended(items),
+            Err(err) => IliaMessage::LoadFailed(format!("{:#}", err)),
+        }
+    }
 }
 
 /// Provide some initial configuration to app to facilitate testing
 #[derive(Debug, Clone)]
-pub struct IliaConfiguration<T: MaybeSend> {
+pub struct IliaConfiguration<T: MaybeSend + ItemDescriptor> {
     /**
-     * A function that returns the list of Items
+     * The source of the list of Items
      */
-    pub item_loader: fn() -> Vec<T>,
+    pub provider: Arc<dyn ItemProvider<Item = T>>,
+    /// Placeholder of the query entry, naming what is listed, such as from `Config::prompt`
     pub entry_hint: String,
+    pub config: Config,
+    /// Name under which a cancelled query is kept, to restore it when the launcher is reopened
+    /// within `Config::query_restore_ms`.  Not kept when unset.
+    pub query_session: Option<&'static str>,
+    /// A paragraph shown above the entry, such as what a prompt is for
+    pub message: Option<String>,
+    /// Hide what is typed and show no list, for secrets such as passphrases.  Enter takes the
+    /// item the provider `create`s from the entry.
+    pub obscured: bool,
+    /// How the query picks and orders items, in place of fuzzy matching.  The matcher decides
+    /// whether case matters, so the palette offers no toggle for it.
+    pub matcher: Option<Arc<dyn Matcher>>,
+    /// Switch like alt-tab: while the modifiers held at launch stay held, Tab advances the
+    /// selection and Shift+Tab moves it back, and releasing them takes the selected item
+    pub release_to_execute: bool,
+    /// Stay open until an item is taken, for dedicated terminals: Escape clears the query rather
+    /// than closing, losing focus or clicking outside doesn't close, and the palette offers no
+    /// command which quits
+    pub kiosk: bool,
+    /// Close, or take the selected item, once the launcher has waited this long untouched
+    pub timeout: Option<Timeout>,
+    /// How the items are ordered while the query is empty
+    pub empty_order: EmptyOrder,
+    /// Where the uses of items are kept for the frecency order, rather than in
+    /// `$XDG_STATE_HOME/ilia`
+    pub frecency_file: Option<PathBuf>,
+    /// The id of the item action which takes the selected item, such as "kill", in place of its
+    /// primary `exec`.  Items without it are not taken.
+    pub action: Option<String>,
+}
+
+impl<T: MaybeSend + ItemDescriptor> IliaConfiguration<T> {
+    /// List the provider's items fuzzy matched, in the order loaded, and close once one is taken.
+    /// Front-ends change the rest with struct update syntax.
+    pub fn new(
+        provider: Arc<dyn ItemProvider<Item = T>>,
+        entry_hint: String,
+        config: Config,
+    ) -> Self {
+        IliaConfiguration {
+            provider,
+            entry_hint,
+            config,
+            query_session: None,
+            message: None,
+            obscured: false,
+            matcher: None,
+            release_to_execute: false,
+            kiosk: false,
+            timeout: None,
+            empty_order: EmptyOrder::Load,
+            frecency_file: None,
+            action: None,
+        }
+    }
+}
+
+/// Orders of the items shown for the empty query
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EmptyOrder {
+    /// As the provider loaded them
+    #[default]
+    Load,
+    /// By title, ignoring case
+    Alphabetical,
+    /// The most often and recently taken first, then the rest as loaded
+    Frecency,
+    /// The most often taken first, however long ago, then the rest as loaded
+    Frequency,
+}
+
+impl EmptyOrder {
+    /// Whether the order counts the items taken
+    fn counts_uses(self) -> bool {
+        matches!(self, EmptyOrder::Frecency | EmptyOrder::Frequency)
+    }
 }
 
 impl<T: MaybeSend + Clone + ItemDescriptor + 'static> Ilia<T> {
     pub fn new(flags: IliaConfiguration<T>) -> (Self, Task<IliaMessage<T>>) {
+        // Only the provider is needed to load, the rest of the configuration moves into the app
+        let provider = flags.provider.clone();
+        let batches = provider.load_incremental();
+        // Batches are listed as they arrive, so only a whole load leaves the list blank meanwhile
+        let loading = batches.is_none();
+        let startup = Task::batch([
+            // The backdrop covers the whole output, with the launcher panel centered on it
+            if flags.config.backdrop {
+                window::get_oldest().and_then(|id| window::change_mode(id, Mode::Fullscreen))
+            } else {
+                Task::none()
+            },
+            match batches {
+                // Nothing may be loaded for a while, so the entry takes focus before the first batch
+                Some(batches) => Task::batch([
+                    text_input::focus::<IliaMessage<T>>(ENTRY_WIDGET_ID.clone()),
+                    Task::run(batches, IliaMessage::from_batch),
+                ]),
+                None => Task::perform(async move { provider.load() }, IliaMessage::from_load),
+            },
+            if flags.config.inhibit_idle {
+                window::get_oldest()
+                    .and_then(|id| {
+                        window::run_with_handle(id, |handle| {
+                            idle::inhibit(handle)
+                                .map(Arc::new)
+                                .map_err(|err| format!("{:#}", err))
+                        })
+                    })
+                    .map(IliaMessage::IdleInhibited)
+            } else {
+                Task::none()
+            },
+        ]);
+
         (
             Self {
                 state: State {
-                    entry: String::new(),
+                    entry: flags
+                        .query_session
+                        .filter(|_| flags.config.query_restore_ms > 0)
+                        .and_then(|session| {
+                            session::restore(
+                                session,
+                                Duration::from_millis(flags.config.query_restore_ms),
+                            )
+                        })
+                        .unwrap_or_default(),
                     apps: vec![],
                     selected_index: 0,
                     received_focus: false,
+                    started: Instant::now(),
+                    deadline: flags.timeout.map(|timeout| Instant::now() + timeout.after),
+                    theme: flags
+                        .config
+                        .theme
+                        .as_deref()
+                        .and_then(theme_named)
+                        .unwrap_or(Theme::Nord),
+                    case_sensitive: flags.config.case_sensitive,
+                    details: flags.config.details,
+                    reloading: false,
+                    loading,
+                    enrichment: vec![],
+                    pending_query: None,
+                    busy: None,
+                    error: None,
+                    modifiers: Modifiers::empty(),
+                    launch_modifiers: Modifiers::empty(),
+                    viewport: None,
+                    row_height: None,
+                    idle_inhibitor: None,
+                    regex: RegexMatcher::default(),
+                    entry_hidden: false,
+                    ellipsis: Ellipsis::End,
+                    history: history::History::new(flags.config.history_size),
+                    history_index: None,
+                    frecency: frecency::Frecency::default(),
+                    favorites: favorites::Favorites::default(),
+                    filter_cache: RefCell::new(None),
+                    matches: RefCell::new(None),
+                    confirming: None,
+                    acting_on: None,
+                    marked: vec![],
+                    pointer: None,
+                    hover_selects: false,
+                    wheel: 0.0,
                 },
-                flags: flags.clone(),
+                flags,
             },
-            Task::perform(async {}, move |_| {
-                IliaMessage::ModelLoaded((flags.item_loader)())
-            }),
+            startup,
         )
     }
 
+    /// Describe the widgets of the UI for the current state
+    pub fn view_tree(&self) -> ViewTree {
+        // Only the rows in view are drawn, as building thousands of rows makes every redraw slow
+        let count = self.visible_titles().len();
+        let drawn = match (self.state.viewport, self.state.row_height) {
+            (Some(viewport), Some(row_height)) => drawn_rows(
+                count,
+                self.columns(),
+                row_height,
+                viewport.bounds().height,
+                viewport.absolute_offset().y,
+            ),
+            // Until a row has been measured, there is no telling which are in view
+            _ => 0..count,
+        };
+        // The scroll math takes every row to be one height, so when some rows have a subtitle
+        // the others leave room for one
+        let subtitled = self.palette_query().is_none()
+            && self.filtered_items().any(|item| item.subtitle().is_some());
+        let rows = if self.palette_query().is_some() {
+            self.palette_entries()
+                .iter()
+                .enumerate()
+                .skip(drawn.start)
+                .take(drawn.len())
+                .map(|(index, entry)| self.row_view(index, &entry.name))
+                .collect::<Vec<_>>()
+        } else {
+            // A section is headed where it starts, which may be above the rows in view
+            let mut group = drawn
+                .start
+                .checked_sub(1)
+                .and_then(|index| self.filtered_items().nth(index))
+                .and_then(|item| item.group().map(String::from));
+            self.filtered_items()
+                .enumerate()
+                .skip(drawn.start)
+                .take(drawn.len())
+                .map(|(index, item)| {
+                    let header = item
+                        .group()
+                        .filter(|name| group.as_deref() != Some(*name))
+                        .map(String::from);
+                    group = item.group().map(String::from);
+                    RowView {
+                        header,
+                        subtitle: item
+                            .subtitle()
+                            .map(String::from)
+                            .or_else(|| subtitled.then(String::new)),
+                        badge: item.badge().map(String::from),
+                        icon: item.icon().and_then(icon::cached),
+                        marked: self.state.marked.iter().any(|id| *id == item.id()),
+                        ..self.row_view(index, item.title())
+                    }
+                })
+                .collect()
+        };
+        let query = [("query", self.state.entry.trim())];
+        let create = match self.creation().filter(|_| !self.flags.obscured) {
+            Some(_) => Some(self.row_view(count, &tr_with("create-row", &query))),
+            None => self
+                .runs_query()
+                .then(|| self.row_view(count, &tr_with("run-row", &query))),
+        };
+        let placeholder = (count == 0
+            && !self.state.loading
+            && !self.flags.obscured
+            && !self.state.entry.is_empty())
+        .then(|| {
+            self.flags
+                .config
+                .no_matches_message
+                .clone()
+                .unwrap_or_else(|| tr("no-matches"))
+        });
+
+        ViewTree {
+            message: self.flags.message.clone(),
+            entry: (!self.state.entry_hidden).then(|| EntryView {
+                hint: self.flags.entry_hint.clone(),
+                value: self.state.entry.clone(),
+                obscured: self.flags.obscured,
+                counter: (!self.flags.obscured).then(|| self.counter()),
+            }),
+            status: if self.state.loading {
+                Some(tr("loading"))
+            } else {
+                self.state.reloading.then(|| tr("refreshing"))
+            },
+            countdown: self.countdown(),
+            error: self.state.error.clone().or_else(|| {
+                let (_, pattern) = self.regex_query()?;
+                self.state
+                    .regex
+                    .error(&pattern)
+                    .map(|error| tr_with("regex-invalid", &[("error", &error)]))
+            }),
+            confirmation: self
+                .state
+                .confirming
+                .as_ref()
+                .and_then(|item| item.confirmation()),
+            pinned: self.pinned_rows(),
+            rows_before: drawn.start,
+            rows,
+            rows_after: count - drawn.end,
+            placeholder,
+            create,
+            details: self.details(),
+        }
+    }
+
+    // The time left before the timeout, while it runs
+    fn countdown(&self) -> Option<CountdownView> {
+        let (deadline, timeout) = self.state.deadline.zip(self.flags.timeout)?;
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        let secs = remaining.as_secs_f32().ceil().to_string();
+        let id = if timeout.take_selected {
+            "timeout-take"
+        } else {
+            "timeout-close"
+        };
+        Some(CountdownView {
+            label: tr_with(id, &[("secs", &secs)]),
+            left: if timeout.after.is_zero() {
+                0.0
+            } else {
+                remaining.as_secs_f32() / timeout.after.as_secs_f32()
+            },
+        })
+    }
+
+    // The details of the selected item, if they are toggled on
+    fn details(&self) -> Vec<(String, String)> {
+        if !self.state.details || self.flags.obscured || self.palette_query().is_some() {
+            return vec![];
+        }
+        self.selected_entry()
+            .map(|item| {
+                item.details()
+                    .into_iter()
+                    .map(|(label, value)| (label.to_string(), value))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    // Describe the list row at an index, highlighting the part matching the query
+    fn row_view(&self, index: usize, name: &str) -> RowView {
+        let (matcher, query) = self.matcher();
+        let palette_query = self.palette_query().map(str::trim);
+        RowView {
+            label: match self.state.busy {
+                Some((busy_index, _)) if busy_index == index => format!("{} …", name),
+                _ => name.to_string(),
+            },
+            header: None,
+            subtitle: None,
+            badge: None,
+            shortcut: index
+                .checked_sub(self.first_row_in_view())
+                .filter(|offset| *offset < SHORTCUT_ROWS)
+                .map(|offset| (offset + 1).to_string()),
+            icon: None,
+            // The palette is always matched fuzzily, whatever the front-end's matcher
+            highlights: match palette_query {
+                Some(query) => fuzzy::match_ranges(name, query, false),
+                None => highlight_terms(matcher, &self.terms(&query), name),
+            },
+            selected: self.state.selected_index == index,
+            marked: false,
+            stale: self.state.reloading,
+        }
+    }
+
     /// Entry-point from `iced` into app to construct UI
     pub fn view(&self) -> Element<'_, IliaMessage<T>> {
-        // Create the list UI elements based on the `ItemDescriptor` model
-        let app_elements: Vec<Element<IliaMessage<T>>> = self
-            .state
-            .apps
-            .iter()
-            .filter(|e| Self::text_entry_filter(e, &self.state))
-            .enumerate()
-            .map(|(index, entry)| {
-                let name = entry.title();
-                let selected = self.state.selected_index == index;
-                button(name)
-                    .style(move |theme, status| {
-                        if selected {
-                            primary(theme, status)
-                        } else {
-                            text(theme, status)
-                        }
-                    })
-                    .width(Length::Fill)
-                    .on_press(IliaMessage::ExecuteSelected())
-                    .into()
-            })
-            .collect();
+        profile::phase("first_frame", None);
+        let tree = self.view_tree();
+        // Mirror the layout for right to left scripts
+        let align_x = if is_rtl() {
+            Horizontal::Right
+        } else {
+            Horizontal::Left
+        };
+
+        let columns = self.columns();
+        let window_width = window_size(self.state.entry_hidden, self.flags.config.columns).width;
+        let max_chars = (self.flags.config.list_width.unwrap_or(window_width)
+            / columns as f32
+            / AVERAGE_GLYPH_WIDTH) as usize;
+        // Rows without an icon, or whose icon is still being looked up, keep its room when others
+        // have one, so all rows are one height and titles line up
+        let iconed = self.palette_query().is_none()
+            && self.filtered_items().any(|item| item.icon().is_some());
+        let row_element = |index: usize, row: RowView| -> Element<IliaMessage<T>> {
+            let item_row = match row.icon {
+                Some(path) => ItemRow::new(row.label).icon(icon_element(path)),
+                None if iconed => {
+                    let size = f32::from(icon::ICON_SIZE);
+                    ItemRow::new(row.label).icon(Space::new(size, size))
+                }
+                None => ItemRow::new(row.label),
+            };
+            item_row
+                .subtitle(row.subtitle)
+                .badge(row.badge)
+                .shortcut(row.shortcut)
+                .highlights(row.highlights)
+                .highlight_color(self.state.theme.palette().primary)
+                .selected(row.selected)
+                .marked(row.marked)
+                .stale(row.stale)
+                .max_chars(max_chars)
+                .ellipsis(self.state.ellipsis)
+                .align_x(align_x)
+                .on_press(IliaMessage::RowClicked(index))
+                .on_hover(IliaMessage::RowHovered(index))
+                .into()
+        };
+        let total = tree.rows_before + tree.rows.len() + tree.rows_after;
+        let rows_before = tree.rows_before;
+        let mut rows = (rows_before..)
+            .zip(tree.rows)
+            .map(|(index, row)| (row.header.clone(), row_element(index, row)))
+            .peekable();
+        let mut app_elements: Vec<Element<IliaMessage<T>>> = vec![];
+        if columns == 1 {
+            for (index, (header, row)) in (rows_before..).zip(rows) {
+                // Headers are few, so the scroll math, which takes every row to be one height,
+                // spreads them over the rows as it does the rule
+                if let Some(header) = header {
+                    app_elements.push(
+                        container(
+                            Text::new(header)
+                                .size(12)
+                                .style(iced::widget::text::secondary),
+                        )
+                        .padding([4, 8])
+                        .width(Length::Fill)
+                        .align_x(align_x)
+                        .into(),
+                    );
+                }
+                app_elements.push(row);
+                // A faint rule tells the pinned rows from the ordinary matches under them
+                if index + 1 == tree.pinned && tree.pinned < total {
+                    app_elements.push(horizontal_rule(1).into());
+                }
+            }
+        } else {
+            // Each line of the grid is filled out, so cells of the last line keep their width
+            while rows.peek().is_some() {
+                // The cells of a grid are not sectioned
+                let cells: Vec<Element<IliaMessage<T>>> =
+                    rows.by_ref().take(columns).map(|(_, cell)| cell).collect();
+                let padding = columns - cells.len();
+                app_elements.push(
+                    Row::with_children(cells)
+                        .extend((0..padding).map(|_| Space::with_width(Length::Fill).into()))
+                        .into(),
+                );
+            }
+        }
+
+        // Rows out of view are stood in for by space of their height
+        let line_height = self.state.row_height.unwrap_or_default();
+        let lines_before = tree.rows_before / columns;
+        let lines_after = tree.rows_after.div_ceil(columns);
+
+        let unmatched = tree.placeholder.is_some();
+        let confirming = tree.confirmation.is_some();
+        let list = mouse_area(
+            Column::new()
+                .push(Space::with_height(lines_before as f32 * line_height))
+                .extend(app_elements)
+                .push(Space::with_height(lines_after as f32 * line_height)),
+        );
+        // Taking the wheel here keeps the scrollable from scrolling the list itself
+        let list = if self.flags.config.wheel_selects {
+            list.on_scroll(IliaMessage::WheelScrolled)
+        } else {
+            list
+        };
 
         // Bare bones!
         // TODO: Fancier layout?
-        column![
-            text_input(&self.flags.entry_hint, &self.state.entry)
-                .id(ENTRY_WIDGET_ID.clone())
-                .on_input(IliaMessage::EntryUpdate)
-                .width(320),
-            scrollable(Column::with_children(app_elements))
-                .width(320)
-                .id(ITEMS_WIDGET_ID.clone()),
-        ]
-        .into()
+        let panel = Column::new()
+            .push_maybe(
+                tree.message
+                    .map(|message| Text::new(message).width(Length::Fill).align_x(align_x)),
+            )
+            .push_maybe(tree.entry.map(|entry| {
+                // The typed text stops short of the counter, which is drawn over the entry's end
+                let counter_width = entry.counter.as_ref().map_or(0.0, |counter| {
+                    counter.chars().count() as f32 * COUNTER_GLYPH_WIDTH + 2.0 * COUNTER_PADDING
+                });
+                let input = text_input(&entry.hint, &entry.value)
+                    .id(ENTRY_WIDGET_ID.clone())
+                    .on_input(IliaMessage::EntryUpdate)
+                    .secure(entry.obscured)
+                    .align_x(align_x)
+                    .padding(Padding::new(5.0).right(5.0 + counter_width))
+                    .width(self.flags.config.entry_length());
+                let counter = entry.counter.map(|counter| {
+                    container(
+                        container(Text::new(counter).size(12))
+                            .padding([0.0, COUNTER_PADDING])
+                            .style(container::rounded_box),
+                    )
+                    .align_right(Length::Fill)
+                    .center_y(Length::Fill)
+                    .padding([0, 5])
+                });
+                Stack::new().push(input).push_maybe(counter)
+            }))
+            .push_maybe(
+                tree.status
+                    .map(|status| Text::new(status).size(12).align_x(align_x)),
+            )
+            .push_maybe(tree.countdown.map(|countdown| {
+                Column::new()
+                    .push(progress_bar(0.0..=1.0, countdown.left).height(2))
+                    .push(Text::new(countdown.label).size(12).align_x(align_x))
+                    .width(self.flags.config.entry_length())
+            }))
+            .push_maybe(tree.error.map(|error| {
+                Text::new(error)
+                    .size(14)
+                    .style(iced::widget::text::danger)
+                    .align_x(align_x)
+            }))
+            .push_maybe(tree.placeholder.map(|placeholder| {
+                Text::new(placeholder)
+                    .width(self.flags.config.list_length())
+                    .align_x(align_x)
+                    .style(iced::widget::text::secondary)
+            }))
+            .push_maybe(tree.confirmation.map(|question| {
+                Column::new()
+                    .push(Text::new(question).align_x(align_x))
+                    .push(
+                        Text::new(tr("confirm-hint"))
+                            .size(12)
+                            .style(iced::widget::text::secondary)
+                            .align_x(align_x),
+                    )
+                    .width(self.flags.config.list_length())
+            }))
+            .push_maybe(
+                (!self.flags.obscured && !unmatched && !confirming).then(|| {
+                    mouse_area(
+                        scrollable(list)
+                            .width(self.flags.config.list_length())
+                            .id(ITEMS_WIDGET_ID.clone())
+                            .on_scroll(IliaMessage::Scrolled),
+                    )
+                    .on_move(IliaMessage::PointerMoved)
+                }),
+            )
+            .push_maybe(tree.create.map(|row| row_element(total, row)))
+            .push_maybe((!tree.details.is_empty()).then(|| {
+                Column::with_children(tree.details.into_iter().map(|(label, value)| {
+                    Row::new()
+                        .push(
+                            Text::new(label)
+                                .size(12)
+                                .style(iced::widget::text::secondary),
+                        )
+                        .push(Text::new(value).size(12))
+                        .spacing(8)
+                        .into()
+                }))
+                .width(self.flags.config.list_length())
+            }));
+
+        if self.flags.config.backdrop {
+            // Presses within the panel are captured by `opaque`, so only those outside dismiss
+            let size = window_size(self.state.entry_hidden, self.flags.config.columns);
+            let panel = container(panel)
+                .width(size.width)
+                .height(size.height)
+                .style(|theme: &Theme| container::Style {
+                    background: Some(theme.palette().background.into()),
+                    ..container::Style::default()
+                });
+            mouse_area(center(opaque(panel)))
+                .on_press(IliaMessage::Dismissed)
+                .into()
+        } else {
+            panel.into()
+        }
     }
 
     /// Entry-point from `iced` to handle user and system events
@@ -133,44 +1030,293 @@ impl<T: MaybeSend + Clone + ItemDescriptor + 'static> Ilia<T> {
         match message {
             // The model has been loaded, initialize the UI
             IliaMessage::ModelLoaded(items) => {
+                profile::phase("model_load", None);
+                self.state.loading = false;
+                crash::record_model_size(items.len());
+                let selected = self.selected_entry().map(|item| item.id().into_owned());
                 self.state.apps = items;
+                self.forget_matches();
+                self.state.reloading = false;
+                // Kept over the warning otherwise, the failure of an action is what to show
+                if let Some(warning) = self.flags.provider.load_warning() {
+                    self.state.error = Some(warning);
+                }
+                // A reload keeps the query, so the selection follows its item wherever it moved
+                self.select_id(selected);
+                Task::batch([
+                    text_input::focus::<IliaMessage<T>>(ENTRY_WIDGET_ID.clone()),
+                    self.enrich(0),
+                    self.resolve_icons(self.state.apps.iter()),
+                ])
+            }
+            // The batch may match the query better than the items before it, but the selection
+            // stays on its item rather than jumping while the user looks
+            IliaMessage::ModelAppended(items) => {
+                profile::phase("first_batch", None);
+                let selected = self.selected_entry().map(|item| item.id().into_owned());
+                let start = self.state.apps.len();
+                self.state.apps.extend(items);
+                crash::record_model_size(self.state.apps.len());
+                self.forget_matches();
+                self.select_id(selected);
+                Task::batch([
+                    self.enrich(start),
+                    self.resolve_icons(self.state.apps[start..].iter()),
+                ])
+            }
+            // Explain the failure in the UI rather than panicking, a missing compositor socket is common
+            IliaMessage::LoadFailed(err) => {
+                self.state.loading = false;
+                self.state.reloading = false;
+                self.state.error = Some(err);
                 text_input::focus::<IliaMessage<T>>(ENTRY_WIDGET_ID.clone())
             }
+            // Results of a superseded query are ignored, the matching query is still in flight
+            IliaMessage::QueryLoaded(query, items) => {
+                if query != self.state.entry {
+                    return Task::none();
+                }
+                self.state.pending_query = None;
+                crash::record_model_size(items.len());
+                self.state.apps = items;
+                self.forget_matches();
+                self.state.selected_index = 0;
+                Task::batch([self.enrich(0), self.resolve_icons(self.state.apps.iter())])
+            }
+            // Replace a single item with its enriched form
+            IliaMessage::ItemUpdated(index, item) => {
+                let task = self.resolve_icons(std::iter::once(&item));
+                if let Some(slot) = self.state.apps.get_mut(index) {
+                    *slot = item;
+                    self.forget_matches();
+                }
+                task
+            }
             // Rebuild the select list based on the updated text entry
             IliaMessage::EntryUpdate(entry_text) => {
+                // Alt chords are shortcuts, not text, but the entry widget inserts them regardless
+                if self.state.modifiers.alt() {
+                    return Task::none();
+                }
+                // The palette lists the actions of the item selected as it opens
+                if !entry_text.starts_with(PALETTE_PREFIX) {
+                    self.state.acting_on = None;
+                } else if self.palette_query().is_none() && !self.flags.obscured {
+                    self.state.acting_on = self.selected_entry().cloned().map(|item| {
+                        let actions = self.flags.provider.actions(&item);
+                        (item, actions)
+                    });
+                }
                 self.state.entry = entry_text;
+                *self.state.matches.get_mut() = None;
                 self.state.selected_index = 0;
+                self.state.error = None;
+                self.state.history_index = None;
 
-                Task::none()
+                if self.flags.provider.is_queryable() && self.palette_query().is_none() {
+                    self.query()
+                } else {
+                    Task::none()
+                }
             }
             // Launch an application selected by the user
-            IliaMessage::ExecuteSelected() => {
-                if let Some(entry) = self.selected_entry() {
-                    entry.exec().expect("Failed to launch app");
+            IliaMessage::ExecuteSelected() => self.execute_selected(),
+            IliaMessage::SubmitQuery(query) => match self.flags.provider.submit(&query) {
+                Some(item) => self.execute(item),
+                None => Task::none(),
+            },
+            // A failed action was likely on a stale item, so show why and refresh the model
+            IliaMessage::ExecuteFinished(result) => {
+                self.state.busy = None;
+                match result {
+                    Ok(()) => Task::none(),
+                    Err(err) => {
+                        self.state.error = Some(err);
+                        self.reload()
+                    }
+                }
+            }
+            // Clicking takes the row clicked, even if the keyboard has moved the selection since
+            IliaMessage::RowClicked(index) => {
+                self.state.selected_index = index;
+                self.execute_selected()
+            }
+            // The row under the pointer is selected as it is hovered, as if navigated to, but
+            // not as the list scrolls or reorders under a pointer left still
+            IliaMessage::RowHovered(index) => {
+                if self.state.hover_selects {
+                    self.state.selected_index = index;
                 }
                 Task::none()
             }
-            // Handle keyboard entries
-            IliaMessage::KeyEvent(key) => match key {
-                Key::Named(Named::Escape) => exit(0),
-                Key::Named(Named::ArrowUp) => self.navigate_items(-1),
-                Key::Named(Named::ArrowDown) => self.navigate_items(1),
-                Key::Named(Named::Enter) => {
-                    if let Some(entry) = self.selected_entry() {
-                        entry.exec().expect("Failed to launch app");
+            // The list follows the selection, so hovering stops until the pointer moves again
+            IliaMessage::WheelScrolled(delta) => {
+                self.state.hover_selects = false;
+                self.state.wheel -= match delta {
+                    ScrollDelta::Lines { y, .. } => y,
+                    ScrollDelta::Pixels { y, .. } => y / WHEEL_STEP_PIXELS,
+                };
+                let steps = self.state.wheel.trunc();
+                self.state.wheel -= steps;
+                let delta = steps.signum() as isize * self.columns() as isize;
+                let tasks: Vec<_> = (0..steps.abs() as usize)
+                    .map(|_| self.navigate_items(delta))
+                    .collect();
+                Task::batch(tasks)
+            }
+            IliaMessage::PointerMoved(point) => {
+                if self.state.pointer != Some(point) {
+                    self.state.pointer = Some(point);
+                    self.state.hover_selects = true;
+                }
+                Task::none()
+            }
+            // Handle keyboard entries, which the selection follows rather than the pointer
+            IliaMessage::KeyEvent(key, modifiers) => {
+                self.state.hover_selects = false;
+                self.state.deadline = None;
+                match key {
+                    // A confirmation takes only its answer
+                    Key::Named(Named::Enter) if self.state.confirming.is_some() => {
+                        self.confirm(true)
                     }
-                    Task::none()
+                    Key::Named(Named::Escape) if self.state.confirming.is_some() => {
+                        self.confirm(false)
+                    }
+                    _ if self.state.confirming.is_some() => Task::none(),
+                    // Escape cancels a running action before it dismisses the launcher
+                    Key::Named(Named::Escape) => match self.state.busy.take() {
+                        Some((_, handle)) => {
+                            handle.abort();
+                            Task::none()
+                        }
+                        None if self.flags.kiosk => {
+                            self.update(IliaMessage::EntryUpdate(String::new()))
+                        }
+                        None => self.cancel(),
+                    },
+                    Key::Named(Named::F5) => self.reload(),
+                    Key::Named(Named::F2) => self.toggle_details(),
+                    Key::Named(Named::F8) => self.switch_theme(),
+                    // Alt with a digit takes the row showing it, with anything else jumps
+                    Key::Character(c) if modifiers.alt() && !modifiers.control() => {
+                        match c.as_str().parse() {
+                            Ok(digit @ 1..=SHORTCUT_ROWS) => self.take_shortcut(digit),
+                            _ => self.jump_to_initial(c.as_str()),
+                        }
+                    }
+                    Key::Character(c) if modifiers.control() && c.as_str() == "r" => self.reload(),
+                    Key::Character(c) if modifiers.control() && c.as_str() == "d" => {
+                        self.toggle_favorite()
+                    }
+                    Key::Named(Named::Tab) if self.flags.release_to_execute => {
+                        self.navigate_items(if modifiers.shift() { -1 } else { 1 })
+                    }
+                    Key::Named(Named::Tab) if !modifiers.shift() => self.complete(),
+                    Key::Named(Named::ArrowUp) if modifiers.control() => self.recall(1),
+                    Key::Named(Named::ArrowDown) if modifiers.control() => self.recall(-1),
+                    // Up from the first row recalls, as in a shell, unless a query is being typed
+                    Key::Named(Named::ArrowUp)
+                        if self.state.selected_index == 0
+                            && !self.flags.config.wrap_around
+                            && (self.state.entry.is_empty()
+                                || self.state.history_index.is_some()) =>
+                    {
+                        self.recall(1)
+                    }
+                    Key::Named(Named::PageUp) => self.navigate_pages(-1),
+                    Key::Named(Named::PageDown) => self.navigate_pages(1),
+                    Key::Named(Named::Home) => self.select(0),
+                    Key::Named(Named::End) => {
+                        let last = self.selectable_rows().saturating_sub(1);
+                        self.select(last)
+                    }
+                    Key::Named(Named::ArrowUp) => self.navigate_items(-(self.columns() as isize)),
+                    Key::Named(Named::ArrowDown) => self.navigate_items(self.columns() as isize),
+                    // In a grid the entry's cursor gives way to moving along the line
+                    Key::Named(Named::ArrowLeft) if self.columns() > 1 => self.navigate_items(-1),
+                    Key::Named(Named::ArrowRight) if self.columns() > 1 => self.navigate_items(1),
+                    Key::Named(Named::Enter) if modifiers.control() => self.open_actions(),
+                    Key::Named(Named::Enter) if modifiers.shift() => self.submit_query(),
+                    Key::Named(Named::Space)
+                        if modifiers.control() && self.flags.provider.marks() =>
+                    {
+                        self.toggle_mark()
+                    }
+                    Key::Named(Named::Enter)
+                        if !self.state.marked.is_empty() && self.palette_query().is_none() =>
+                    {
+                        self.execute_marked()
+                    }
+                    Key::Named(Named::Enter) => self.execute_selected(),
+                    _ => Task::none(),
                 }
-                _ => Task::none(),
-            },
+            }
             // Handle window events
             IliaMessage::GainedFocus => {
                 self.state.received_focus = true;
                 Task::none()
             }
             IliaMessage::LostFocus => {
-                if self.state.received_focus {
-                    exit(0);
+                let grace = Duration::from_millis(self.flags.config.focus_grace_ms);
+                if !self.flags.kiosk
+                    && self.state.received_focus
+                    && self.state.started.elapsed() >= grace
+                {
+                    self.cancel();
+                }
+                Task::none()
+            }
+            IliaMessage::TimeoutTicked => match self.state.deadline {
+                Some(deadline) if Instant::now() >= deadline => {
+                    self.state.deadline = None;
+                    match self.flags.timeout {
+                        Some(Timeout {
+                            take_selected: true,
+                            ..
+                        }) => self.execute_selected(),
+                        _ => self.cancel(),
+                    }
+                }
+                _ => Task::none(),
+            },
+            IliaMessage::Dismissed if self.flags.kiosk => Task::none(),
+            IliaMessage::Dismissed => self.cancel(),
+            IliaMessage::ModifiersChanged(modifiers) => {
+                self.state.modifiers = modifiers;
+                if !self.flags.release_to_execute {
+                    return Task::none();
+                }
+                let launch = self.state.launch_modifiers;
+                if launch.is_empty() {
+                    // Only those held as the launcher opens are the binding's, bar Shift, which
+                    // moves the selection back and types capitals
+                    let grace = Duration::from_millis(self.flags.config.focus_grace_ms);
+                    if self.state.started.elapsed() < grace {
+                        self.state.launch_modifiers = modifiers.difference(Modifiers::SHIFT);
+                    }
+                    Task::none()
+                } else if !modifiers.intersects(launch) && self.state.busy.is_none() {
+                    self.execute_selected()
+                } else {
+                    Task::none()
+                }
+            }
+            IliaMessage::Scrolled(viewport) => {
+                // Every row is the same height, so one is the drawn list over its rows
+                let rows = self.visible_titles().len().div_ceil(self.columns());
+                if rows > 0 {
+                    self.state.row_height = Some(viewport.content_bounds().height / rows as f32);
+                }
+                self.state.viewport = Some(viewport);
+                Task::none()
+            }
+            // Drawing the list again shows the icons found
+            IliaMessage::IconsResolved => Task::none(),
+            IliaMessage::IdleInhibited(result) => {
+                match result {
+                    Ok(inhibitor) => self.state.idle_inhibitor = Some(inhibitor),
+                    Err(err) => eprintln!("Not inhibiting idle: {}", err),
                 }
                 Task::none()
             }
@@ -179,89 +1325,1130 @@ impl<T: MaybeSend + Clone + ItemDescriptor + 'static> Ilia<T> {
 
     /// The `iced` entry-point to setup event listeners
     pub fn subscription(&self) -> iced::Subscription<IliaMessage<T>> {
+        // Items pushed by the provider replace the model as a reload would
+        let watch = self.flags.provider.watch().map(|updates| {
+            iced::Subscription::run_with_id("provider-watch", updates.map(IliaMessage::ModelLoaded))
+        });
+
         // Framework code to integrate with underlying user interface devices; keyboard, mouse.
-        event::listen_with(|event, _status, _| match event {
+        let events = event::listen_with(|event, _status, _| match event {
             Event::Window(window::Event::Focused) => Some(IliaMessage::GainedFocus),
             Event::Window(window::Event::Unfocused) => Some(IliaMessage::LostFocus),
+            Event::Keyboard(iced::keyboard::Event::ModifiersChanged(modifiers)) => {
+                Some(IliaMessage::ModifiersChanged(modifiers))
+            }
+            // The keypad's Enter is also `Named::Enter`, so it acts as the main keyboard's
             Event::Keyboard(iced::keyboard::Event::KeyPressed {
-                modifiers: _,
+                modifiers,
                 text: _,
                 key,
-                location: _,
+                location,
                 modified_key: _,
-                physical_key: _,
-            }) => Some(IliaMessage::KeyEvent(key)),
+                physical_key,
+            }) => Some(IliaMessage::KeyEvent(
+                keypad_key(key, location, physical_key, modifiers),
+                modifiers,
+            )),
             _ => None,
-        })
+        });
+
+        let countdown = self
+            .state
+            .deadline
+            .map(|_| iced::Subscription::run(timeout::ticks).map(|()| IliaMessage::TimeoutTicked));
+
+        iced::Subscription::batch([events].into_iter().chain(watch).chain(countdown))
     }
 
-    // Return ref to the selected item from the app list after applying filter
-    fn selected_entry(&self) -> Option<&T> {
+    /// The theme selected by the user, for the `iced` application
+    pub fn theme(&self) -> Theme {
+        self.state.theme.clone()
+    }
+
+    /// The application style, dimming the desktop behind the backdrop
+    pub fn style(&self, theme: &Theme) -> Appearance {
+        let appearance = theme.default_style();
+        if self.flags.config.backdrop {
+            Appearance {
+                background_color: Color::BLACK
+                    .scale_alpha(self.flags.config.backdrop_opacity.clamp(0.0, 1.0)),
+                ..appearance
+            }
+        } else {
+            appearance
+        }
+    }
+
+    // Return the items from the app list matching the query, best match first, as many as are
+    // listed
+    fn filtered_items(&self) -> impl Iterator<Item = &T> {
+        let matches = self.matches();
+        let listed = match self.flags.config.max_rows {
+            0 => matches.len(),
+            max_rows => matches.len().min(max_rows),
+        };
+        (0..listed).map(move |position| &self.state.apps[matches[position]])
+    }
+
+    // Return the indices of every item matching the query, best match first, as last matched
+    // unless the query or the items have changed since
+    fn matches(&self) -> Rc<[usize]> {
         self.state
-            .apps
-            .iter()
-            .filter(|e| Self::text_entry_filter(e, &self.state))
-            .nth(self.state.selected_index)
+            .matches
+            .borrow_mut()
+            .get_or_insert_with(|| self.match_items().into())
+            .clone()
     }
 
-    // Change the selected item and update the UI with the returned `Task`
-    fn navigate_items(&mut self, delta: i32) -> iced::Task<IliaMessage<T>> {
-        let new_index = (self.state.selected_index as i32 + delta) as usize;
-        let size = self
-            .state
+    // Forget what the query matched, as the items changed
+    fn forget_matches(&mut self) {
+        *self.state.filter_cache.get_mut() = None;
+        *self.state.matches.get_mut() = None;
+    }
+
+    // Match the query against each item, returning the indices of those matching, best first
+    fn match_items(&self) -> Vec<usize> {
+        // Queryable providers have done the filtering already
+        if self.flags.provider.is_queryable() {
+            return (0..self.state.apps.len()).collect();
+        }
+        if self.state.entry.is_empty() {
+            return self.ordered_items();
+        }
+
+        let started = profile::enabled().then(Instant::now);
+        let (matcher, query) = self.matcher();
+        let terms = self.terms(&query);
+        let exclude = self.excluded_terms();
+        // A fuzzy query extending the last one can only match fewer items, so only those are
+        // scored.  Patterns, exclusions and the matchers of front-ends may match more.
+        let case_sensitive = self.case_sensitive(&query, false);
+        let narrowing =
+            self.flags.matcher.is_none() && self.regex_query().is_none() && exclude.is_empty();
+        let mut cache = self.state.filter_cache.borrow_mut();
+        let candidates: Vec<usize> = match cache.take() {
+            Some(cache)
+                if narrowing
+                    && query.starts_with(&cache.query)
+                    && (case_sensitive || !cache.case_sensitive) =>
+            {
+                cache.indices
+            }
+            _ => (0..self.state.apps.len()).collect(),
+        };
+
+        let mut scored: Vec<(u32, usize)> = candidates
+            .into_iter()
+            .filter_map(|index| {
+                let item = &self.state.apps[index];
+                if exclude
+                    .iter()
+                    .any(|term| score_item(matcher, term, item).is_some())
+                {
+                    return None;
+                }
+                score_terms(matcher, &terms, item).map(|score| (score, index))
+            })
+            .collect();
+        if narrowing {
+            *cache = Some(FilterCache {
+                query: query.to_string(),
+                case_sensitive,
+                indices: scored.iter().map(|(_, index)| *index).collect(),
+            });
+        }
+
+        // Stable, so equal matches keep the load order
+        scored.sort_by_key(|(score, _)| Reverse(*score));
+        let mut indices: Vec<usize> = scored.into_iter().map(|(_, index)| index).collect();
+        if let Some(started) = started {
+            profile::phase("first_filter", Some(started.elapsed()));
+        }
+        self.pin_alias(&mut indices);
+        indices
+    }
+
+    // Move the item the query is an alias of first, adding it if it did not match
+    fn pin_alias(&self, indices: &mut Vec<usize>) {
+        let Some(aliased) = self.aliased_index() else {
+            return;
+        };
+        indices.retain(|index| *index != aliased);
+        indices.insert(0, aliased);
+    }
+
+    // Return the index of the item the query is an alias of, if it is one
+    fn aliased_index(&self) -> Option<usize> {
+        let id = self.flags.config.aliases.get(self.state.entry.trim())?;
+        self.state
             .apps
             .iter()
-            .filter(|e| Self::text_entry_filter(e, &self.state))
-            .count();
-
-        if (0..size).contains(&new_index) {
-            self.state.selected_index = new_index;
+            .position(|item| item.id() == id.as_str())
+    }
 
-            snap_to::<IliaMessage<T>>(
-                ITEMS_WIDGET_ID.clone(),
-                RelativeOffset {
-                    x: 0.0,
-                    y: new_index as f32 * ITEM_HEIGHT_SCALE_FACTOR,
-                },
-            )
-        } else {
-            Task::none() // If the new location is out of bounds, ignore
+    // Return how many rows lead the list for other reasons than matching: the item of an alias,
+    // or the favorites and the items taken before when the empty query lists them first
+    fn pinned_rows(&self) -> usize {
+        if self.palette_query().is_some() || self.flags.provider.is_queryable() {
+            return 0;
+        }
+        if !self.state.entry.is_empty() {
+            return usize::from(self.aliased_index().is_some());
         }
+        let frecency = self.flags.empty_order.counts_uses();
+        self.filtered_items()
+            .take_while(|item| {
+                let id = item.id();
+                self.state.favorites.contains(&id)
+                    || (frecency && self.state.frecency.score(&id) > 0)
+            })
+            .count()
     }
 
-    // Compute the items in the list to display based on the model
-    fn text_entry_filter(entry: &T, model: &State<T>) -> bool {
-        entry
-            .title()
-            .to_lowercase()
-            .contains(&model.entry.to_lowercase())
+    // Return the index of every item, in the order the front-end chose for the empty query
+    fn ordered_items(&self) -> Vec<usize> {
+        let apps = &self.state.apps;
+        let mut indices: Vec<usize> = (0..apps.len()).collect();
+        // Stable, so equal items keep the load order
+        match self.flags.empty_order {
+            EmptyOrder::Load => {}
+            EmptyOrder::Alphabetical => {
+                indices.sort_by_cached_key(|index| apps[*index].title().to_lowercase())
+            }
+            EmptyOrder::Frequency => indices
+                .sort_by_cached_key(|index| Reverse(self.state.frecency.count(&apps[*index].id()))),
+            EmptyOrder::Frecency => indices
+                .sort_by_cached_key(|index| Reverse(self.state.frecency.score(&apps[*index].id()))),
+        }
+        // Favorites lead whatever the order
+        indices.sort_by_cached_key(|index| !self.state.favorites.contains(&apps[*index].id()));
+        indices
     }
-}
 
-// Create iced settings from input
-pub fn iced_settings(program_name: &str) -> Settings {
-    iced::settings::Settings {
-        id: Some(program_name.to_string()),
-        fonts: vec![],
-        default_font: Font::DEFAULT,
-        default_text_size: Pixels::from(18),
-        antialiasing: true,
+    // Return the cells per line of the list, which lists the palette's actions in one column
+    fn columns(&self) -> usize {
+        if self.palette_query().is_some() {
+            1
+        } else {
+            self.flags.config.columns.max(1)
+        }
     }
-}
 
-pub fn window_settings(program_name: &str) -> window::Settings {
-    window::Settings {
-        size: Size {
-            width: 320.0,
-            height: 200.0,
+    // Return the titles of the rows in the list, from the `ItemDescriptor` model or the palette actions
+    fn visible_titles(&self) -> Vec<Cow<'_, str>> {
+        if self.palette_query().is_some() {
+            self.palette_entries()
+                .into_iter()
+                .map(|entry| Cow::Owned(entry.name))
+                .collect()
+        } else {
+            self.filtered_items()
+                .map(|item| Cow::Borrowed(item.title()))
+                .collect()
+        }
+    }
+
+    // Return the item creating something from the query, when the provider offers one
+    fn creation(&self) -> Option<T> {
+        // Secrets are taken exactly as typed
+        let query = if self.flags.obscured {
+            self.state.entry.as_str()
+        } else {
+            self.state.entry.trim()
+        };
+        if query.is_empty() || self.palette_query().is_some() {
+            return None;
+        }
+        self.flags.provider.create(query)
+    }
+
+    // Return ref to the selected item from the app list after applying filter
+    fn selected_entry(&self) -> Option<&T> {
+        self.filtered_items().nth(self.state.selected_index)
+    }
+
+    // Select the item with the id, or keep the selected place if it is gone
+    fn select_id(&mut self, id: Option<String>) {
+        let position = id.and_then(|id| {
+            self.filtered_items()
+                .position(|item| item.id() == id.as_str())
+        });
+        let size = self.filtered_items().count();
+        self.state.selected_index = position
+            .unwrap_or(self.state.selected_index)
+            .min(size.saturating_sub(1));
+    }
+
+    // Return the query for the command palette, if the entry opens it
+    fn palette_query(&self) -> Option<&str> {
+        self.state.entry.strip_prefix(PALETTE_PREFIX)
+    }
+
+    // Return the themes to cycle through: the configured ones, or every built-in theme
+    fn themes(&self) -> Vec<Theme> {
+        let themes: Vec<Theme> = self
+            .flags
+            .config
+            .themes
+            .iter()
+            .filter_map(|name| {
+                let theme = theme_named(name);
+                if theme.is_none() {
+                    eprintln!("Ignoring unknown theme {}", name);
+                }
+                theme
+            })
+            .collect();
+        if themes.is_empty() {
+            Theme::ALL.to_vec()
+        } else {
+            themes
+        }
+    }
+
+    // Return the actions the palette offers: those of the item it opened on, then ilia's own
+    // which the front-end supports
+    fn offered_entries(&self) -> Vec<PaletteAction<T>> {
+        let actions = self
+            .state
+            .acting_on
+            .iter()
+            .flat_map(|(_, actions)| actions.iter().cloned())
+            .map(PaletteAction::from);
+        let launcher = palette::launcher_actions().into_iter().filter(|action| {
+            match action.id {
+                // The matcher decides whether case matters
+                "toggle-case-sensitivity" => self.flags.matcher.is_none(),
+                // Editing the config quits
+                "open-config" => !self.flags.kiosk,
+                _ => true,
+            }
+        });
+        actions.chain(launcher).collect()
+    }
+
+    // Return the palette actions matching the palette query
+    fn palette_entries(&self) -> Vec<PaletteAction<T>> {
+        let query = self.palette_query().unwrap_or_default().trim();
+        let mut entries = self.offered_entries();
+        entries
+            .retain(|entry| query.is_empty() || fuzzy::score(&entry.name, query, false).is_some());
+        entries
+    }
+
+    // Return the matcher for the items with the query to give it: the regex matcher for a
+    // pattern, or else the matcher of the front-end or the fuzzy matcher following the case toggle
+    // with the query's excluded terms left out
+    fn matcher(&self) -> (&dyn Matcher, Cow<'_, str>) {
+        if let Some(query) = self.regex_query() {
+            return query;
+        }
+        let matcher: &dyn Matcher = match &self.flags.matcher {
+            Some(matcher) => matcher.as_ref(),
+            None if self.case_sensitive(&self.state.entry, false) => &FuzzyMatcher {
+                case_sensitive: true,
+            },
+            None => &FuzzyMatcher {
+                case_sensitive: false,
+            },
+        };
+        (matcher, query::parse(&self.state.entry).include)
+    }
+
+    // Split the query into the terms an item must all match, in any order.  A pattern is one.
+    fn terms<'q>(&self, query: &'q str) -> Vec<&'q str> {
+        if self.regex_query().is_some() {
+            vec![query]
+        } else {
+            query.split_whitespace().collect()
+        }
+    }
+
+    // Return the terms of the query excluding the items they match, none for a pattern
+    fn excluded_terms(&self) -> Vec<&str> {
+        if self.regex_query().is_some() {
+            return vec![];
+        }
+        query::parse(&self.state.entry).exclude
+    }
+
+    // Whether the query matches case sensitively: always once toggled on, or else with smart case
+    // when the query has an uppercase letter, not counting escapes such as `\S` in a pattern
+    fn case_sensitive(&self, query: &str, pattern: bool) -> bool {
+        if self.state.case_sensitive {
+            return true;
+        }
+        if !self.flags.config.smart_case {
+            return false;
+        }
+        let mut chars = query.chars();
+        while let Some(c) = chars.next() {
+            if pattern && c == '\\' {
+                chars.next();
+            } else if c.is_uppercase() {
+                return true;
+            }
+        }
+        false
+    }
+
+    // Return the regex matcher and pattern when the entry is a regular expression, following the
+    // case toggle
+    fn regex_query(&self) -> Option<(&dyn Matcher, Cow<'_, str>)> {
+        let pattern = self
+            .state
+            .entry
+            .strip_prefix(REGEX_PREFIX)
+            .or_else(|| self.flags.config.regex.then_some(self.state.entry.as_str()))
+            .filter(|_| self.palette_query().is_none())?;
+        let pattern = if self.case_sensitive(pattern, true) {
+            Cow::Borrowed(pattern)
+        } else {
+            Cow::Owned(format!("(?i){}", pattern))
+        };
+        Some((&self.state.regex, pattern))
+    }
+
+    // Close the launcher without a selection, keeping the query if the front-end restores it
+    fn cancel(&self) -> ! {
+        if let Some(session) = self.flags.query_session.filter(|_| !self.flags.obscured) {
+            if !self.state.entry.is_empty() {
+                if let Err(err) = session::save(session, &self.state.entry) {
+                    eprintln!("Not keeping query: {:#}", err);
+                }
+            }
+        }
+        exit(EXIT_CANCELLED);
+    }
+
+    // Take primary action on the selected palette command or item
+    fn execute_selected(&mut self) -> Task<IliaMessage<T>> {
+        if self.palette_query().is_some() {
+            let entry = self
+                .palette_entries()
+                .into_iter()
+                .nth(self.state.selected_index);
+            match entry.map(|entry| entry.run) {
+                Some(Run::Launcher(run)) => run(self),
+                Some(Run::Item(run)) => self.run_action(run),
+                None => Task::none(),
+            }
+        } else {
+            if self.state.busy.is_some() {
+                return Task::none();
+            }
+            // Past the filtered items, the pinned row creates from the query
+            match self.selected_entry().cloned().or_else(|| self.creation()) {
+                Some(entry) => self.execute(entry),
+                None if self.runs_query() => self.run_query(),
+                None => self.submit_query(),
+            }
+        }
+    }
+
+    // How many items match the query out of how many there are
+    fn counter(&self) -> String {
+        if self.palette_query().is_some() {
+            format!(
+                "{}/{}",
+                self.palette_entries().len(),
+                self.offered_entries().len()
+            )
+        } else if self.flags.provider.is_queryable() {
+            self.state.apps.len().to_string()
+        } else {
+            format!("{}/{}", self.matches().len(), self.state.apps.len())
+        }
+    }
+
+    // Replace the query with the title of the selected row, to refine it or take it with Enter
+    fn complete(&mut self) -> Task<IliaMessage<T>> {
+        if self.flags.obscured {
+            return Task::none();
+        }
+        let title = match self.palette_query() {
+            Some(_) => self
+                .palette_entries()
+                .get(self.state.selected_index)
+                .map(|entry| format!("{}{}", PALETTE_PREFIX, entry.name)),
+            None => self.selected_entry().map(|item| item.title().to_string()),
+        };
+        match title {
+            Some(title) => Task::batch([
+                self.update(IliaMessage::EntryUpdate(title)),
+                text_input::move_cursor_to_end(ENTRY_WIDGET_ID.clone()),
+            ]),
+            None => Task::none(),
+        }
+    }
+
+    // Open the palette on the actions of the selected item
+    fn open_actions(&mut self) -> Task<IliaMessage<T>> {
+        if self.flags.obscured || self.palette_query().is_some() {
+            return Task::none();
+        }
+        Task::batch([
+            self.update(IliaMessage::EntryUpdate(PALETTE_PREFIX.to_string())),
+            text_input::move_cursor_to_end(ENTRY_WIDGET_ID.clone()),
+        ])
+    }
+
+    // Mark the selected item to be taken with the others marked, or unmark it
+    fn toggle_mark(&mut self) -> Task<IliaMessage<T>> {
+        if self.palette_query().is_some() {
+            return Task::none();
+        }
+        let Some(id) = self.selected_entry().map(|item| item.id().into_owned()) else {
+            return Task::none();
+        };
+        match self.state.marked.iter().position(|marked| *marked == id) {
+            Some(position) => {
+                self.state.marked.remove(position);
+            }
+            None => self.state.marked.push(id),
+        }
+        Task::none()
+    }
+
+    // Pin the selected item to lead the list for the empty query, or unpin it
+    fn toggle_favorite(&mut self) -> Task<IliaMessage<T>> {
+        if self.flags.obscured || self.palette_query().is_some() {
+            return Task::none();
+        }
+        let Some(id) = self.selected_entry().map(|item| item.id().into_owned()) else {
+            return Task::none();
+        };
+        if let Err(err) = self.state.favorites.toggle(&id) {
+            eprintln!("Not keeping favorite: {:#}", err);
+        }
+        *self.state.matches.get_mut() = None;
+        // The empty query's order changed, so the selection follows the item into view
+        self.select_id(Some(id));
+        self.select(self.state.selected_index)
+    }
+
+    // Take the marked items together, those gone since they were marked left out
+    fn execute_marked(&mut self) -> Task<IliaMessage<T>> {
+        if self.state.busy.is_some() {
+            return Task::none();
+        }
+        let items: Vec<T> = self
+            .state
+            .marked
+            .iter()
+            .filter_map(|id| self.state.apps.iter().find(|item| item.id() == *id))
+            .cloned()
+            .collect();
+        let provider = self.flags.provider.clone();
+        let (task, handle) = Task::perform(
+            async move {
+                provider
+                    .exec_marked(&items)
+                    .map_err(|err| format!("{:#}", err))
+            },
+            IliaMessage::ExecuteFinished,
+        )
+        .abortable();
+        self.state.busy = Some((self.state.selected_index, handle));
+        task
+    }
+
+    // Whether the query is offered as a command line to run, as nothing matches it
+    fn runs_query(&self) -> bool {
+        self.flags.config.run_unmatched
+            && !self.flags.obscured
+            && self.palette_query().is_none()
+            && !self.state.entry.trim().is_empty()
+            && self.creation().is_none()
+            && self.matches().is_empty()
+    }
+
+    // Run the query as a shell command line, as an app is started
+    fn run_query(&mut self) -> Task<IliaMessage<T>> {
+        let query = self.state.entry.trim().to_string();
+        if let Err(err) = self.state.history.push(&query) {
+            eprintln!("Not keeping query in history: {:#}", err);
+        }
+        Task::perform(
+            async move {
+                launch::check_allowed(&query).map_err(|err| format!("{:#}", err))?;
+                match launch::command("sh").arg("-c").arg(&query).spawn() {
+                    Ok(_) => exit(0),
+                    Err(err) => Err(format!("Unable to run {}: {}", query, err)),
+                }
+            },
+            IliaMessage::ExecuteFinished,
+        )
+    }
+
+    // Take the typed text rather than an item, if there is any
+    fn submit_query(&self) -> Task<IliaMessage<T>> {
+        if self.state.entry.is_empty() || self.palette_query().is_some() {
+            return Task::none();
+        }
+        Task::done(IliaMessage::SubmitQuery(self.state.entry.clone()))
+    }
+
+    // Take primary action on the item, once confirmed if it asks to be
+    fn execute(&mut self, entry: T) -> Task<IliaMessage<T>> {
+        if self.state.busy.is_none() && entry.confirmation().is_some() {
+            self.state.confirming = Some(entry);
+            return Task::none();
+        }
+        self.take(entry)
+    }
+
+    // Answer the confirmation of the item waiting for it, taking the item or going back
+    fn confirm(&mut self, confirmed: bool) -> Task<IliaMessage<T>> {
+        match self.state.confirming.take() {
+            Some(entry) if confirmed => self.take(entry),
+            _ => Task::none(),
+        }
+    }
+
+    // Take primary action on the item, unless an action is already running
+    fn take(&mut self, entry: T) -> Task<IliaMessage<T>> {
+        if self.state.busy.is_some() {
+            return Task::none();
+        }
+        let run: ActionFn<T> = match &self.flags.action {
+            None => Arc::new(T::exec),
+            Some(id) => {
+                let action = self
+                    .flags
+                    .provider
+                    .actions(&entry)
+                    .into_iter()
+                    .find(|action| action.id == id);
+                match action {
+                    Some(action) => action.run,
+                    None => {
+                        self.state.error = Some(tr_with(
+                            "action-missing",
+                            &[("item", entry.title()), ("action", id)],
+                        ));
+                        return Task::none();
+                    }
+                }
+            }
+        };
+        // Kept before the action runs, as many exit once done
+        if !self.flags.obscured && !self.state.entry.trim().is_empty() {
+            if let Err(err) = self.state.history.push(&self.state.entry) {
+                eprintln!("Not keeping query in history: {:#}", err);
+            }
+        }
+        if !self.flags.obscured && self.flags.empty_order.counts_uses() {
+            if let Err(err) = self.state.frecency.record(&entry.id()) {
+                eprintln!("Not counting use: {:#}", err);
+            }
+            *self.state.matches.get_mut() = None;
+        }
+        // Run off the update loop, as actions may wait on IPC or the network
+        let (task, handle) = Task::perform(
+            async move { run(&entry).map_err(|err| format!("{:#}", err)) },
+            IliaMessage::ExecuteFinished,
+        )
+        .abortable();
+        self.state.busy = Some((self.state.selected_index, handle));
+        task
+    }
+
+    // Perform an action of the item the palette opened on, off the update loop as `take` runs
+    // its primary action
+    fn run_action(&mut self, run: ActionFn<T>) -> Task<IliaMessage<T>> {
+        if self.state.busy.is_some() {
+            return Task::none();
+        }
+        let Some(item) = self.state.acting_on.as_ref().map(|(item, _)| item.clone()) else {
+            return Task::none();
+        };
+        let (task, handle) = Task::perform(
+            async move { run(&item).map_err(|err| format!("{:#}", err)) },
+            IliaMessage::ExecuteFinished,
+        )
+        .abortable();
+        self.state.busy = Some((self.state.selected_index, handle));
+        task
+    }
+
+    // Cycle to the next theme, keeping it in the config
+    fn switch_theme(&mut self) -> Task<IliaMessage<T>> {
+        let themes = self.themes();
+        let next = themes
+            .iter()
+            .position(|theme| *theme == self.state.theme)
+            .map_or(0, |index| (index + 1) % themes.len());
+        self.state.theme = themes[next].clone();
+        // Keep the theme for the next launch
+        if let Err(err) = Config::save_theme(&self.state.theme.to_string()) {
+            eprintln!("Not saving theme: {:#}", err);
+        }
+        Task::none()
+    }
+
+    fn toggle_case_sensitivity(&mut self) -> Task<IliaMessage<T>> {
+        self.state.case_sensitive = !self.state.case_sensitive;
+        *self.state.matches.get_mut() = None;
+        Task::none()
+    }
+
+    fn toggle_details(&mut self) -> Task<IliaMessage<T>> {
+        self.state.details = !self.state.details;
+        Task::none()
+    }
+
+    // Open the config in the default editor and quit, or explain why it can't be opened
+    fn edit_config(&mut self) -> Task<IliaMessage<T>> {
+        match open_config() {
+            Ok(()) => exit(0),
+            Err(err) => {
+                self.state.error = Some(format!("{:#}", err));
+                Task::none()
+            }
+        }
+    }
+
+    // Ask the provider for late-arriving data on each item from `start` on in the background.
+    // From 0 the model is new, so enrichment of the previous one is aborted.
+    fn enrich(&mut self, start: usize) -> Task<IliaMessage<T>> {
+        let provider = self.flags.provider.clone();
+        let items = self.state.apps[start..].to_vec();
+        let updates =
+            stream::iter(items.into_iter().enumerate()).filter_map(move |(index, item)| {
+                let provider = provider.clone();
+                async move { provider.enrich(&item).map(|item| (start + index, item)) }
+            });
+
+        let (task, handle) = Task::run(updates, |(index, item)| {
+            IliaMessage::ItemUpdated(index, item)
+        })
+        .abortable();
+        // Dropping the previous handles aborts updates meant for the previous model
+        if start == 0 {
+            self.state.enrichment.clear();
+        }
+        self.state.enrichment.push(handle.abort_on_drop());
+        task
+    }
+
+    // Look up the icons of the items which are not known yet in the background
+    fn resolve_icons<'a>(&self, items: impl Iterator<Item = &'a T>) -> Task<IliaMessage<T>>
+    where
+        T: 'a,
+    {
+        let mut icons: Vec<String> = items
+            .filter_map(|item| item.icon())
+            .filter(|icon| icon::is_unresolved(icon))
+            .map(String::from)
+            .collect();
+        if icons.is_empty() {
+            return Task::none();
+        }
+        icons.sort_unstable();
+        icons.dedup();
+        let theme = self.flags.config.icon_theme.clone();
+        Task::perform(
+            async move { icon::resolve(&icons, theme.as_deref()) },
+            |_| IliaMessage::IconsResolved,
+        )
+    }
+
+    // Send the current query to a queryable provider, cancelling any query still in flight
+    fn query(&mut self) -> Task<IliaMessage<T>> {
+        let provider = self.flags.provider.clone();
+        let query = self.state.entry.clone();
+        let (task, handle) = Task::perform(
+            async move { (provider.query(&query), query) },
+            |(result, query)| match result {
+                Ok(items) => IliaMessage::QueryLoaded(query, items),
+                Err(err) => IliaMessage::LoadFailed(format!("{:#}", err)),
+            },
+        )
+        .abortable();
+        self.state.pending_query = Some(handle.abort_on_drop());
+        task
+    }
+
+    // Load the items again from the provider in the background, keeping the current ones visible
+    fn reload(&mut self) -> Task<IliaMessage<T>> {
+        if self.flags.provider.is_queryable() {
+            return self.query();
+        }
+        if self.state.reloading {
+            return Task::none();
+        }
+        self.state.reloading = true;
+
+        let provider = self.flags.provider.clone();
+        let current = self.state.apps.clone();
+        Task::perform(
+            async move { provider.refresh(&current) },
+            IliaMessage::from_load,
+        )
+    }
+
+    // Return the number of selectable rows, including the one creating from the query
+    fn selectable_rows(&self) -> usize {
+        if self.palette_query().is_some() {
+            self.palette_entries().len()
+        } else {
+            self.filtered_items().count()
+                + usize::from(self.creation().is_some() || self.runs_query())
+        }
+    }
+
+    // Change the selected item and update the UI with the returned `Task`
+    fn navigate_items(&mut self, delta: isize) -> iced::Task<IliaMessage<T>> {
+        let size = self.selectable_rows();
+        let new_index = self
+            .state
+            .selected_index
+            .checked_add_signed(delta)
+            .filter(|index| *index < size);
+
+        match new_index {
+            Some(new_index) => self.select(new_index),
+            None if self.flags.config.wrap_around && size > 0 => {
+                self.select(if delta > 0 { 0 } else { size - 1 })
+            }
+            None => Task::none(), // If the new location is out of bounds, ignore
+        }
+    }
+
+    // Move the selection by `pages` of the rows in view, stopping at either end of the list
+    fn navigate_pages(&mut self, pages: isize) -> iced::Task<IliaMessage<T>> {
+        let size = self.selectable_rows();
+        if size == 0 {
+            return Task::none();
+        }
+        // Unmeasured, the list has not overflowed, so a page is all of it
+        let lines = match (self.state.viewport, self.state.row_height) {
+            (Some(viewport), Some(row_height)) if row_height > 0.0 => {
+                ((viewport.bounds().height / row_height).floor() as usize).max(1)
+            }
+            _ => size,
+        };
+        let delta = pages.saturating_mul((lines * self.columns()) as isize);
+        let index = self
+            .state
+            .selected_index
+            .saturating_add_signed(delta)
+            .min(size - 1);
+        self.select(index)
+    }
+
+    // Replace the query with one `delta` queries older in the history, emptying it past the newest
+    fn recall(&mut self, delta: isize) -> iced::Task<IliaMessage<T>> {
+        let back = match self.state.history_index {
+            Some(index) => index.checked_add_signed(delta),
+            None if delta > 0 => Some(delta as usize - 1),
+            None => return Task::none(),
+        };
+        let Some(back) = back else {
+            return self.update(IliaMessage::EntryUpdate(String::new()));
+        };
+        let Some(query) = self.state.history.get(back).map(String::from) else {
+            return Task::none();
+        };
+        let task = self.update(IliaMessage::EntryUpdate(query));
+        self.state.history_index = Some(back);
+        Task::batch([
+            task,
+            text_input::move_cursor_to_end(ENTRY_WIDGET_ID.clone()),
+        ])
+    }
+
+    // The first row wholly in view, 0 until the list overflows
+    fn first_row_in_view(&self) -> usize {
+        match (self.state.viewport, self.state.row_height) {
+            (Some(viewport), Some(row_height)) if row_height > 0.0 => {
+                (viewport.absolute_offset().y / row_height).ceil() as usize * self.columns()
+            }
+            _ => 0,
+        }
+    }
+
+    // Take the row which the digit is shown on
+    fn take_shortcut(&mut self, digit: usize) -> iced::Task<IliaMessage<T>> {
+        let index = self.first_row_in_view() + digit - 1;
+        if index >= self.selectable_rows() {
+            return Task::none();
+        }
+        self.state.selected_index = index;
+        self.execute_selected()
+    }
+
+    // Select the alphabetically first visible row starting with the character
+    fn jump_to_initial(&mut self, initial: &str) -> iced::Task<IliaMessage<T>> {
+        let initial = initial.to_lowercase();
+        let target = self
+            .visible_titles()
+            .into_iter()
+            .map(|title| title.to_lowercase())
+            .enumerate()
+            .filter(|(_, title)| title.starts_with(&initial))
+            .min_by(|(_, a), (_, b)| a.cmp(b))
+            .map(|(index, _)| index);
+
+        match target {
+            Some(index) => self.select(index),
+            None => Task::none(),
+        }
+    }
+
+    // Move the selection to a visible row and scroll it into view, keeping the configured margin
+    fn select(&mut self, index: usize) -> iced::Task<IliaMessage<T>> {
+        self.state.selected_index = index;
+
+        // Until the list overflows there is nothing to scroll
+        let (Some(viewport), Some(row_height)) = (self.state.viewport, self.state.row_height)
+        else {
+            return Task::none();
+        };
+        // The grid scrolls by its lines
+        let columns = self.columns();
+        let rows = self.visible_titles().len().div_ceil(columns);
+        match scroll_offset(
+            index / columns,
+            rows,
+            row_height,
+            viewport.bounds().height,
+            viewport.absolute_offset().y,
+            self.flags.config.scroll_margin,
+        ) {
+            Some(y) => scroll_to(ITEMS_WIDGET_ID.clone(), AbsoluteOffset { x: 0.0, y }),
+            None => Task::none(),
+        }
+    }
+}
+
+// The key to handle for a key pressed: Alt with a keypad digit is the digit, to take a row even
+// while Num Lock is off and makes the key a movement
+fn keypad_key(key: Key, location: Location, physical_key: Physical, modifiers: Modifiers) -> Key {
+    if location != Location::Numpad || !modifiers.alt() {
+        return key;
+    }
+    let digit = match physical_key {
+        Physical::Code(Code::Numpad0) => "0",
+        Physical::Code(Code::Numpad1) => "1",
+        Physical::Code(Code::Numpad2) => "2",
+        Physical::Code(Code::Numpad3) => "3",
+        Physical::Code(Code::Numpad4) => "4",
+        Physical::Code(Code::Numpad5) => "5",
+        Physical::Code(Code::Numpad6) => "6",
+        Physical::Code(Code::Numpad7) => "7",
+        Physical::Code(Code::Numpad8) => "8",
+        Physical::Code(Code::Numpad9) => "9",
+        _ => return key,
+    };
+    Key::Character(digit.into())
+}
+
+// The image of an icon file, drawn at the icon size
+fn icon_element<'a, Message: 'a>(path: PathBuf) -> Element<'a, Message> {
+    let size = Pixels::from(icon::ICON_SIZE);
+    if path.extension().is_some_and(|extension| extension == "svg") {
+        iced::widget::svg(path).width(size).height(size).into()
+    } else {
+        iced::widget::image(path).width(size).height(size).into()
+    }
+}
+
+// The built-in theme with the name, such as "Tokyo Night"
+fn theme_named(name: &str) -> Option<Theme> {
+    Theme::ALL
+        .iter()
+        .find(|theme| theme.to_string() == name)
+        .cloned()
+}
+
+// How well the item matches the query by its title or any of its other fields.  Matches of the
+// other fields count half, so items are found by what they do but ranked by what they are called.
+fn score_item(matcher: &dyn Matcher, query: &str, item: &impl ItemDescriptor) -> Option<u32> {
+    let fields = [item.keywords(), item.comment(), item.exec_hint()]
+        .into_iter()
+        .flatten()
+        .filter_map(|field| matcher.score(query, field))
+        .map(|score| score / 2);
+    matcher
+        .score(query, item.title())
+        .into_iter()
+        .chain(fields)
+        .max()
+}
+
+// How well the item matches every term, in any order, the mean of its scores for each, or None
+// if a term doesn't match.  Items match no terms with a score of 0.
+fn score_terms(matcher: &dyn Matcher, terms: &[&str], item: &impl ItemDescriptor) -> Option<u32> {
+    let mut total = 0u64;
+    for term in terms {
+        total += u64::from(score_item(matcher, term, item)?);
+    }
+    Some(u32::try_from(total / terms.len().max(1) as u64).unwrap_or(u32::MAX))
+}
+
+// The byte ranges of the name matched by any of the terms, merged where they overlap
+fn highlight_terms(matcher: &dyn Matcher, terms: &[&str], name: &str) -> Vec<Range<usize>> {
+    let mut highlights: Vec<Range<usize>> = terms
+        .iter()
+        .flat_map(|term| matcher.highlights(term, name))
+        .collect();
+    highlights.sort_by_key(|range| range.start);
+    let mut merged: Vec<Range<usize>> = vec![];
+    for range in highlights {
+        match merged.last_mut() {
+            Some(last) if last.end >= range.start => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+// The vertical offset of the list which brings the row at `index`, and `margin` rows around it,
+// into view with the least scrolling, or None if they are already visible
+fn scroll_offset(
+    index: usize,
+    rows: usize,
+    row_height: f32,
+    view_height: f32,
+    offset: f32,
+    margin: usize,
+) -> Option<f32> {
+    if index >= rows || row_height <= 0.0 {
+        return None;
+    }
+    // A margin taking up the whole view would leave no room for the row itself
+    let visible_rows = (view_height / row_height).floor() as usize;
+    let margin = margin.min(visible_rows.saturating_sub(1) / 2);
+
+    let top = index.saturating_sub(margin) as f32 * row_height;
+    let bottom = (index + 1 + margin).min(rows) as f32 * row_height;
+    let max_offset = (rows as f32 * row_height - view_height).max(0.0);
+
+    if top < offset {
+        Some(top)
+    } else if bottom > offset + view_height {
+        Some((bottom - view_height).min(max_offset))
+    } else {
+        None
+    }
+}
+
+// The range of the rows in view of the list scrolled to the offset, and a few lines either side.
+// A grid's range covers whole lines.
+fn drawn_rows(
+    rows: usize,
+    columns: usize,
+    line_height: f32,
+    view_height: f32,
+    offset: f32,
+) -> Range<usize> {
+    if line_height <= 0.0 {
+        return 0..rows;
+    }
+    // The list may have shrunk since the offset was reported, and is scrolled back to fit it
+    let lines = rows.div_ceil(columns.max(1));
+    let offset = offset.min((lines as f32 * line_height - view_height).max(0.0));
+    let first = (offset / line_height).floor() as usize;
+    let visible = (view_height / line_height).ceil() as usize;
+    let end = ((first + visible + OVERSCAN_LINES) * columns).min(rows);
+    let start = (first.saturating_sub(OVERSCAN_LINES) * columns).min(end);
+    start..end
+}
+
+/// Open the configuration file in the default editor, creating an empty one if needed
+fn open_config() -> anyhow::Result<()> {
+    let path = Config::path().context("Unable to find config dir")?;
+    if !path.exists() {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).context("Failed to create config dir")?;
+        }
+        std::fs::write(&path, "").context("Failed to create config")?;
+    }
+
+    std::process::Command::new("xdg-open")
+        .arg(&path)
+        .spawn()
+        .context("Failed to open config")
+        .map(|_| ())
+}
+
+/// Run a front-end with the standard settings, window and theme
+pub fn run<T>(program_name: &'static str, flags: IliaConfiguration<T>) -> iced::Result
+where
+    T: MaybeSend + Clone + Debug + ItemDescriptor + 'static,
+{
+    let window = window_settings(program_name, &flags.config);
+    let entry_hidden = flags.config.hides_entry(program_name);
+    let ellipsis = flags.config.ellipsis(program_name);
+    let history_size = flags.config.history_size;
+    let frecency = flags
+        .empty_order
+        .counts_uses()
+        .then(|| flags.frecency_file.clone());
+    launch::configure(program_name, &flags.config);
+    crash::install(program_name);
+
+    iced::application(program_name, Ilia::update, Ilia::view)
+        .settings(iced_settings(program_name))
+        .window(window)
+        .theme(Ilia::theme)
+        .style(Ilia::style)
+        .subscription(Ilia::subscription)
+        .run_with(move || {
+            let (mut ilia, task) = Ilia::new(flags);
+            ilia.state.entry_hidden = entry_hidden;
+            ilia.state.ellipsis = ellipsis;
+            ilia.state.history = history::History::load(program_name, history_size);
+            ilia.state.favorites = favorites::Favorites::load(program_name);
+            if let Some(file) = frecency {
+                ilia.state.frecency = match file {
+                    Some(path) => frecency::Frecency::open(path),
+                    None => frecency::Frecency::load(program_name),
+                };
+            }
+            (ilia, task)
+        })
+}
+
+// Create iced settings from input
+pub fn iced_settings(program_name: &str) -> Settings {
+    iced::settings::Settings {
+        id: Some(program_name.to_string()),
+        fonts: vec![],
+        default_font: Font::DEFAULT,
+        default_text_size: Pixels::from(18),
+        antialiasing: true,
+    }
+}
+
+// The size of the launcher window, shorter without the entry and wider for a grid
+fn window_size(entry_hidden: bool, columns: usize) -> Size {
+    Size {
+        width: if columns > 1 {
+            columns as f32 * GRID_CELL_WIDTH
+        } else {
+            WINDOW_SIZE.width
+        },
+        height: if entry_hidden {
+            WINDOW_SIZE.height - ENTRY_HEIGHT
+        } else {
+            WINDOW_SIZE.height
         },
+    }
+}
+
+pub fn window_settings(program_name: &str, config: &Config) -> window::Settings {
+    window::Settings {
+        size: window_size(config.hides_entry(program_name), config.columns),
         position: window::Position::Centered,
         min_size: None,
         max_size: None,
         visible: true,
         resizable: false,
         decorations: false,
-        transparent: false,
+        transparent: config.backdrop,
         level: Default::default(),
         icon: None,
         platform_specific: PlatformSpecific {
@@ -271,3 +2458,886 @@ pub fn window_settings(program_name: &str) -> window::Settings {
         exit_on_close_request: true,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use iced_core::keyboard::key::Named;
+    use iced_core::keyboard::{Key, Modifiers};
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::testing::{
+        configured, items, loaded, started, with_provider, TestItem, TestProvider, APP_TITLES,
+    };
+
+    /// A user interaction driving the model
+    #[derive(Debug, Clone)]
+    enum Op {
+        Up,
+        Down,
+        Type(char),
+        Delete,
+    }
+
+    fn op() -> impl Strategy<Value = Op> {
+        prop_oneof![
+            Just(Op::Up),
+            Just(Op::Down),
+            proptest::char::range('a', 'e').prop_map(Op::Type),
+            Just(Op::Delete),
+        ]
+    }
+
+    fn titles() -> impl Strategy<Value = Vec<String>> {
+        proptest::collection::vec("[a-eA-E ]{0,8}", 0..40)
+    }
+
+    fn apply(unit: &mut Ilia<TestItem>, op: &Op) {
+        let message = match op {
+            Op::Up => IliaMessage::KeyEvent(Key::Named(Named::ArrowUp), Modifiers::empty()),
+            Op::Down => IliaMessage::KeyEvent(Key::Named(Named::ArrowDown), Modifiers::empty()),
+            Op::Type(c) => IliaMessage::EntryUpdate(format!("{}{}", unit.state.entry, c)),
+            Op::Delete => {
+                let mut entry = unit.state.entry.clone();
+                entry.pop();
+                IliaMessage::EntryUpdate(entry)
+            }
+        };
+        let _ = unit.update(message);
+    }
+
+    fn filtered_titles(unit: &Ilia<TestItem>) -> Vec<String> {
+        unit.filtered_items()
+            .map(|item| item.title.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_alt_jumps_to_first_alphabetical_initial() {
+        let mut unit = loaded(&["Terminal", "files", "Firefox", "Thunderbird"]);
+        let _ = unit.update(IliaMessage::KeyEvent(
+            Key::Character("f".into()),
+            Modifiers::ALT,
+        ));
+        assert_eq!(unit.selected_entry().unwrap().title, "files");
+        let _ = unit.update(IliaMessage::KeyEvent(
+            Key::Character("t".into()),
+            Modifiers::ALT,
+        ));
+        assert_eq!(unit.selected_entry().unwrap().title, "Terminal");
+    }
+
+    #[test]
+    fn test_filter_orders_by_score() {
+        let mut unit = loaded(&["Profile Manager", "Firefox", "Files"]);
+        let _ = unit.update(IliaMessage::EntryUpdate(String::from("fil")));
+        assert_eq!(filtered_titles(&unit), ["Files", "Profile Manager"]);
+        let _ = unit.update(IliaMessage::EntryUpdate(String::from("ffx")));
+        assert_eq!(filtered_titles(&unit), ["Firefox"]);
+    }
+
+    #[test]
+    fn test_reload_keeps_selected_item() {
+        let mut unit = loaded(&["Firefox", "Files", "Terminal"]);
+        let _ = unit.update(IliaMessage::KeyEvent(
+            Key::Named(Named::ArrowDown),
+            Modifiers::empty(),
+        ));
+        let _ = unit.update(IliaMessage::ModelLoaded(items(&[
+            "Calculator",
+            "Firefox",
+            "Files",
+        ])));
+        assert_eq!(unit.selected_entry().unwrap().title, "Files");
+        // Without its item, the selection keeps its place
+        let _ = unit.update(IliaMessage::ModelLoaded(items(&[
+            "Calculator",
+            "Firefox",
+            "Terminal",
+        ])));
+        assert_eq!(unit.selected_entry().unwrap().title, "Terminal");
+    }
+
+    #[test]
+    fn test_alias_pins_item() {
+        let mut unit = loaded(&APP_TITLES);
+        unit.flags
+            .config
+            .aliases
+            .insert(String::from("fi"), String::from("Terminal"));
+        let _ = unit.update(IliaMessage::EntryUpdate(String::from("fi")));
+        assert_eq!(
+            filtered_titles(&unit),
+            ["Terminal", "Firefox", "Files", "Profile Manager"]
+        );
+        assert_eq!(unit.view_tree().pinned, 1);
+        let _ = unit.update(IliaMessage::EntryUpdate(String::from("fil")));
+        assert_eq!(filtered_titles(&unit), ["Files", "Profile Manager"]);
+        assert_eq!(unit.view_tree().pinned, 0);
+    }
+
+    #[test]
+    fn test_page_and_end_navigation() {
+        let key = |named| IliaMessage::KeyEvent(Key::Named(named), Modifiers::empty());
+        let mut unit = loaded(&APP_TITLES);
+        let _ = unit.update(key(Named::End));
+        assert_eq!(unit.state.selected_index, 4);
+        let _ = unit.update(key(Named::Home));
+        assert_eq!(unit.state.selected_index, 0);
+        // The list has not been laid out, so a page is all of it
+        let _ = unit.update(key(Named::PageDown));
+        assert_eq!(unit.state.selected_index, 4);
+        let _ = unit.update(key(Named::PageUp));
+        assert_eq!(unit.state.selected_index, 0);
+    }
+
+    #[test]
+    fn test_grid_navigation() {
+        let key = |named| IliaMessage::KeyEvent(Key::Named(named), Modifiers::empty());
+        let mut unit = loaded(&APP_TITLES);
+        unit.flags.config.columns = 3;
+        let _ = unit.update(key(Named::ArrowDown));
+        assert_eq!(unit.state.selected_index, 3);
+        let _ = unit.update(key(Named::ArrowRight));
+        assert_eq!(unit.state.selected_index, 4);
+        let _ = unit.update(key(Named::ArrowUp));
+        assert_eq!(unit.state.selected_index, 1);
+        let _ = unit.update(key(Named::ArrowLeft));
+        assert_eq!(unit.state.selected_index, 0);
+    }
+
+    #[test]
+    fn test_appended_batches_keep_selected_item() {
+        let mut unit = loaded(&["Firefox", "Files"]);
+        unit.flags.empty_order = EmptyOrder::Alphabetical;
+        unit.forget_matches();
+        let _ = unit.update(IliaMessage::KeyEvent(
+            Key::Named(Named::ArrowDown),
+            Modifiers::empty(),
+        ));
+        assert_eq!(unit.selected_entry().unwrap().title, "Firefox");
+        let _ = unit.update(IliaMessage::ModelAppended(items(&["Calculator"])));
+        assert_eq!(filtered_titles(&unit), ["Calculator", "Files", "Firefox"]);
+        assert_eq!(unit.selected_entry().unwrap().title, "Firefox");
+    }
+
+    #[test]
+    fn test_recall_history() {
+        let up = |control| {
+            IliaMessage::KeyEvent(
+                Key::Named(Named::ArrowUp),
+                if control {
+                    Modifiers::CTRL
+                } else {
+                    Modifiers::empty()
+                },
+            )
+        };
+        let mut unit = loaded(&APP_TITLES);
+        for query in ["term", "fire"] {
+            let _ = unit.update(IliaMessage::EntryUpdate(String::from(query)));
+            let _ = unit.update(IliaMessage::ExecuteSelected());
+            unit.state.busy = None;
+        }
+        let _ = unit.update(IliaMessage::EntryUpdate(String::new()));
+
+        let _ = unit.update(up(false));
+        assert_eq!(unit.state.entry, "fire");
+        let _ = unit.update(up(false));
+        assert_eq!(unit.state.entry, "term");
+        // Past the oldest query nothing changes, and past the newest the entry empties
+        let _ = unit.update(up(true));
+        assert_eq!(unit.state.entry, "term");
+        for _ in 0..2 {
+            let _ = unit.update(IliaMessage::KeyEvent(
+                Key::Named(Named::ArrowDown),
+                Modifiers::CTRL,
+            ));
+        }
+        assert_eq!(unit.state.entry, "");
+        // A typed query is not replaced by Up
+        let _ = unit.update(IliaMessage::EntryUpdate(String::from("fi")));
+        let _ = unit.update(up(false));
+        assert_eq!(unit.state.entry, "fi");
+    }
+
+    #[test]
+    fn test_max_rows() {
+        let titles: Vec<String> = (0..50).map(|index| format!("line {}", index)).collect();
+        let mut unit = loaded(&titles);
+        unit.flags.config.max_rows = 10;
+        assert_eq!(filtered_titles(&unit).len(), 10);
+        let _ = unit.update(IliaMessage::EntryUpdate(String::from("line 4")));
+        assert_eq!(filtered_titles(&unit)[0], "line 4");
+        for _ in 0..20 {
+            let _ = unit.update(IliaMessage::KeyEvent(
+                Key::Named(Named::ArrowDown),
+                Modifiers::empty(),
+            ));
+        }
+        assert_eq!(unit.state.selected_index, 9);
+    }
+
+    #[test]
+    fn test_empty_query_order() {
+        let mut unit = loaded(&["files", "Terminal", "Firefox"]);
+        unit.flags.empty_order = EmptyOrder::Alphabetical;
+        unit.forget_matches();
+        assert_eq!(filtered_titles(&unit), ["files", "Firefox", "Terminal"]);
+
+        unit.flags.empty_order = EmptyOrder::Frecency;
+        unit.forget_matches();
+        let _ = unit.update(IliaMessage::EntryUpdate(String::from("term")));
+        let _ = unit.update(IliaMessage::ExecuteSelected());
+        let _ = unit.update(IliaMessage::EntryUpdate(String::new()));
+        assert_eq!(filtered_titles(&unit), ["Terminal", "files", "Firefox"]);
+        // A query orders by how well items match it
+        let _ = unit.update(IliaMessage::EntryUpdate(String::from("fi")));
+        assert_eq!(filtered_titles(&unit), ["files", "Firefox"]);
+
+        unit.flags.empty_order = EmptyOrder::Frequency;
+        for _ in 0..2 {
+            unit.state.busy = None;
+            let _ = unit.update(IliaMessage::EntryUpdate(String::from("firefox")));
+            let _ = unit.update(IliaMessage::ExecuteSelected());
+        }
+        let _ = unit.update(IliaMessage::EntryUpdate(String::new()));
+        assert_eq!(filtered_titles(&unit), ["Firefox", "Terminal", "files"]);
+    }
+
+    #[test]
+    fn test_submit_query() {
+        let mut unit = with_provider(TestProvider {
+            submits: true,
+            ..TestProvider::new(&APP_TITLES)
+        });
+        let _ = unit.update(IliaMessage::SubmitQuery(String::from("make coffee")));
+        assert!(unit.state.busy.is_some());
+
+        // Only front-ends which take the query run anything
+        let mut unit = loaded(&APP_TITLES);
+        let _ = unit.update(IliaMessage::SubmitQuery(String::from("make coffee")));
+        assert!(unit.state.busy.is_none());
+    }
+
+    #[test]
+    fn test_release_to_execute() {
+        let mut unit = loaded(&APP_TITLES);
+        unit.flags.release_to_execute = true;
+        let _ = unit.update(IliaMessage::ModifiersChanged(Modifiers::ALT));
+        for modifiers in [
+            Modifiers::ALT,
+            Modifiers::ALT,
+            Modifiers::ALT | Modifiers::SHIFT,
+        ] {
+            let _ = unit.update(IliaMessage::KeyEvent(Key::Named(Named::Tab), modifiers));
+        }
+        // Shift coming and going leaves the binding's Alt held
+        let _ = unit.update(IliaMessage::ModifiersChanged(Modifiers::ALT));
+        assert!(unit.state.busy.is_none());
+        let _ = unit.update(IliaMessage::ModifiersChanged(Modifiers::empty()));
+        assert_eq!(unit.state.busy.as_ref().map(|(index, _)| *index), Some(1));
+    }
+
+    #[test]
+    fn test_release_to_execute_ignores_other_modifiers() {
+        let mut unit = loaded(&APP_TITLES);
+        unit.flags.release_to_execute = true;
+        // Typing a capital is not releasing the binding
+        let _ = unit.update(IliaMessage::ModifiersChanged(Modifiers::SHIFT));
+        let _ = unit.update(IliaMessage::ModifiersChanged(Modifiers::empty()));
+        assert!(unit.state.busy.is_none());
+
+        // Nor are modifiers pressed once the launcher is open
+        unit.flags.config.focus_grace_ms = 0;
+        let _ = unit.update(IliaMessage::ModifiersChanged(Modifiers::CTRL));
+        let _ = unit.update(IliaMessage::ModifiersChanged(Modifiers::empty()));
+        assert!(unit.state.busy.is_none());
+    }
+
+    #[test]
+    fn test_obscured_entry_not_debugged() {
+        let mut unit = loaded(&["Firefox"]);
+        unit.flags.obscured = true;
+        let _ = unit.update(IliaMessage::EntryUpdate(String::from("hunter2")));
+        assert!(!format!("{:?}", unit).contains("hunter2"));
+    }
+
+    #[test]
+    fn test_score_item_fields() {
+        struct App;
+        impl ItemDescriptor for App {
+            fn title(&self) -> &str {
+                "Firefox"
+            }
+            fn exec(&self) -> anyhow::Result<()> {
+                Ok(())
+            }
+            fn keywords(&self) -> Option<&str> {
+                Some("Internet;WWW;Browser;Web;")
+            }
+            fn exec_hint(&self) -> Option<&str> {
+                Some("firefox %u")
+            }
+        }
+        let matcher = FuzzyMatcher::default();
+        assert!(score_item(&matcher, "browser", &App).is_some());
+        assert!(score_item(&matcher, "mail", &App).is_none());
+        // The title outranks everything else
+        assert_eq!(
+            score_item(&matcher, "firefox", &App),
+            matcher.score("firefox", "Firefox")
+        );
+    }
+
+    #[test]
+    fn test_filter_regex() {
+        let mut unit = loaded(&APP_TITLES);
+        let _ = unit.update(IliaMessage::EntryUpdate(String::from("/^f.*s$")));
+        assert_eq!(filtered_titles(&unit), ["Files"]);
+        let _ = unit.update(IliaMessage::EntryUpdate(String::from("/code.*insiders")));
+        assert_eq!(filtered_titles(&unit), ["Visual Studio Code - Insiders"]);
+        assert!(unit.view_tree().error.is_none());
+        let _ = unit.update(IliaMessage::EntryUpdate(String::from("/(files")));
+        assert!(filtered_titles(&unit).is_empty());
+        assert!(unit.view_tree().error.is_some());
+    }
+
+    #[test]
+    fn test_filter_all_terms() {
+        let mut unit = started(IliaConfiguration {
+            matcher: Some(Arc::new(SubstringMatcher::default())),
+            ..configured(TestProvider::new(&APP_TITLES))
+        });
+        let _ = unit.update(IliaMessage::EntryUpdate(String::from("insiders code")));
+        assert_eq!(filtered_titles(&unit), ["Visual Studio Code - Insiders"]);
+        assert_eq!(unit.view_tree().rows[0].highlights, [14..18, 21..29]);
+        let _ = unit.update(IliaMessage::EntryUpdate(String::from("code firefox")));
+        assert!(filtered_titles(&unit).is_empty());
+    }
+
+    #[test]
+    fn test_filter_excluded_terms() {
+        let mut unit = loaded(&["kitty", "GNOME Terminal", "Terminal", "Files"]);
+        let _ = unit.update(IliaMessage::EntryUpdate(String::from("term !gnome")));
+        assert_eq!(filtered_titles(&unit), ["Terminal"]);
+        let _ = unit.update(IliaMessage::EntryUpdate(String::from("!kitty !term")));
+        assert_eq!(filtered_titles(&unit), ["Files"]);
+        // Narrowing the excluded term brings back what it no longer matches
+        let _ = unit.update(IliaMessage::EntryUpdate(String::from("term !gnomex")));
+        assert_eq!(filtered_titles(&unit), ["Terminal", "GNOME Terminal"]);
+        // Patterns have no exclusions
+        let _ = unit.update(IliaMessage::EntryUpdate(String::from("/^!")));
+        assert!(filtered_titles(&unit).is_empty());
+    }
+
+    #[test]
+    fn test_filter_smart_case() {
+        let mut unit = loaded(&["Files", "files.txt", "Profile Manager"]);
+        let _ = unit.update(IliaMessage::EntryUpdate(String::from("fil")));
+        assert_eq!(filtered_titles(&unit).len(), 3);
+        let _ = unit.update(IliaMessage::EntryUpdate(String::from("Fil")));
+        assert_eq!(filtered_titles(&unit), ["Files"]);
+        // Escapes are not uppercase letters of a pattern
+        let _ = unit.update(IliaMessage::EntryUpdate(String::from(r"/^f\S+$")));
+        assert_eq!(filtered_titles(&unit), ["Files", "files.txt"]);
+    }
+
+    #[test]
+    fn test_filter_with_matcher() {
+        let mut unit = started(IliaConfiguration {
+            matcher: Some(Arc::new(SubstringMatcher::default())),
+            ..configured(TestProvider::new(&["Profile Manager", "Firefox", "Files"]))
+        });
+        let _ = unit.update(IliaMessage::EntryUpdate(String::from("fi")));
+        assert_eq!(
+            filtered_titles(&unit),
+            ["Firefox", "Files", "Profile Manager"]
+        );
+        let _ = unit.update(IliaMessage::EntryUpdate(String::from("ffx")));
+        assert!(filtered_titles(&unit).is_empty());
+        // Case is up to the matcher
+        let _ = unit.update(IliaMessage::EntryUpdate(String::from(">case")));
+        assert!(unit.palette_entries().is_empty());
+    }
+
+    #[test]
+    fn test_scroll_offset_keeps_margin() {
+        // Five rows of 10px visible out of 20, scrolled to the top
+        assert_eq!(scroll_offset(2, 20, 10.0, 50.0, 0.0, 1), None);
+        assert_eq!(scroll_offset(4, 20, 10.0, 50.0, 0.0, 1), Some(10.0));
+        assert_eq!(scroll_offset(1, 20, 10.0, 50.0, 20.0, 1), Some(0.0));
+    }
+
+    #[test]
+    fn test_scroll_offset_at_list_ends() {
+        assert_eq!(scroll_offset(19, 20, 10.0, 50.0, 0.0, 1), Some(150.0));
+        assert_eq!(scroll_offset(0, 20, 10.0, 50.0, 150.0, 1), Some(0.0));
+        // A margin beyond half the view is capped so the selection can still move
+        assert_eq!(scroll_offset(3, 20, 10.0, 50.0, 0.0, 10), Some(10.0));
+        assert_eq!(scroll_offset(5, 5, 10.0, 50.0, 0.0, 1), None);
+    }
+
+    #[test]
+    fn test_drawn_rows() {
+        // Five rows of 10px visible out of 100
+        assert_eq!(drawn_rows(100, 1, 10.0, 50.0, 0.0), 0..10);
+        assert_eq!(drawn_rows(100, 1, 10.0, 50.0, 400.0), 35..50);
+        assert_eq!(drawn_rows(100, 1, 10.0, 50.0, 950.0), 90..100);
+        // Whole lines of a grid are drawn
+        assert_eq!(drawn_rows(100, 3, 10.0, 50.0, 100.0), 15..60);
+        assert_eq!(drawn_rows(10, 1, 0.0, 50.0, 0.0), 0..10);
+        assert_eq!(drawn_rows(3, 1, 10.0, 50.0, 400.0), 0..3);
+    }
+
+    #[test]
+    fn test_navigate_up_from_first_item() {
+        let mut unit = loaded(&["a", "b", "c"]);
+        let _ = unit.navigate_items(-1);
+        assert_eq!(unit.state.selected_index, 0);
+    }
+
+    #[test]
+    fn test_navigate_down_from_last_item() {
+        let mut unit = loaded(&["a", "b", "c"]);
+        let _ = unit.navigate_items(2);
+        let _ = unit.navigate_items(1);
+        assert_eq!(unit.state.selected_index, 2);
+    }
+
+    #[test]
+    fn test_hover_follows_pointer_moves() {
+        let mut unit = loaded(&["a", "b", "c", "d"]);
+        // The list laid out under a pointer which has not moved selects nothing
+        let _ = unit.update(IliaMessage::RowHovered(3));
+        assert_eq!(unit.state.selected_index, 0);
+        let _ = unit.update(IliaMessage::PointerMoved(Point::new(10.0, 40.0)));
+        let _ = unit.update(IliaMessage::RowHovered(2));
+        assert_eq!(unit.state.selected_index, 2);
+        let _ = unit.update(IliaMessage::KeyEvent(
+            Key::Named(Named::ArrowUp),
+            Modifiers::empty(),
+        ));
+        let _ = unit.update(IliaMessage::RowHovered(3));
+        assert_eq!(unit.state.selected_index, 1);
+        // A click takes its row whatever is selected
+        let _ = unit.update(IliaMessage::RowClicked(3));
+        assert_eq!(unit.state.busy.as_ref().map(|(index, _)| *index), Some(3));
+    }
+
+    #[test]
+    fn test_wheel_steps_selection() {
+        let mut unit = loaded(&["a", "b", "c", "d"]);
+        unit.flags.config.wheel_selects = true;
+        let _ = unit.update(IliaMessage::WheelScrolled(ScrollDelta::Lines {
+            x: 0.0,
+            y: -2.0,
+        }));
+        assert_eq!(unit.state.selected_index, 2);
+        // Smooth scrolling steps once it adds up to a click
+        let pixels = ScrollDelta::Pixels { x: 0.0, y: 12.0 };
+        let _ = unit.update(IliaMessage::WheelScrolled(pixels));
+        assert_eq!(unit.state.selected_index, 2);
+        let _ = unit.update(IliaMessage::WheelScrolled(pixels));
+        assert_eq!(unit.state.selected_index, 1);
+    }
+
+    #[test]
+    fn test_keypad_digits() {
+        let end = Key::Named(Named::End);
+        let numpad1 = Physical::Code(Code::Numpad1);
+        // With Num Lock off the key moves, unless Alt makes it a shortcut
+        assert_eq!(
+            keypad_key(end.clone(), Location::Numpad, numpad1, Modifiers::ALT),
+            Key::Character("1".into())
+        );
+        assert_eq!(
+            keypad_key(end.clone(), Location::Numpad, numpad1, Modifiers::empty()),
+            end
+        );
+        let enter = Key::Named(Named::Enter);
+        assert_eq!(
+            keypad_key(
+                enter.clone(),
+                Location::Numpad,
+                Physical::Code(Code::NumpadEnter),
+                Modifiers::ALT
+            ),
+            enter
+        );
+        // The main keyboard's keys are as pressed
+        let digit = Key::Character("1".into());
+        assert_eq!(
+            keypad_key(
+                digit.clone(),
+                Location::Standard,
+                Physical::Code(Code::Digit1),
+                Modifiers::ALT
+            ),
+            digit
+        );
+    }
+
+    #[test]
+    fn test_alt_digit_takes_row() {
+        let alt = |digit: &str| IliaMessage::KeyEvent(Key::Character(digit.into()), Modifiers::ALT);
+        let mut unit = loaded(&["a", "b", "c"]);
+        let tree = unit.view_tree();
+        let shortcuts: Vec<_> = tree
+            .rows
+            .iter()
+            .map(|row| row.shortcut.as_deref())
+            .collect();
+        assert_eq!(shortcuts, [Some("1"), Some("2"), Some("3")]);
+        let _ = unit.update(alt("5"));
+        assert!(unit.state.busy.is_none());
+        let _ = unit.update(alt("2"));
+        assert_eq!(unit.state.busy.as_ref().map(|(index, _)| *index), Some(1));
+    }
+
+    #[test]
+    fn test_marked_rows() {
+        let mark = IliaMessage::KeyEvent(Key::Named(Named::Space), Modifiers::CTRL);
+        let down = IliaMessage::KeyEvent(Key::Named(Named::ArrowDown), Modifiers::empty());
+        let mut unit = with_provider(TestProvider {
+            marks: true,
+            ..TestProvider::new(&APP_TITLES)
+        });
+        let _ = unit.update(mark.clone());
+        let _ = unit.update(down.clone());
+        let _ = unit.update(down);
+        let _ = unit.update(mark.clone());
+        let _ = unit.update(IliaMessage::EntryUpdate(String::from("fi")));
+        let marked: Vec<bool> = unit.view_tree().rows.iter().map(|row| row.marked).collect();
+        assert_eq!(marked, [true, false, true]);
+        // Marking again unmarks, and marks outlive the query which listed them
+        let _ = unit.update(mark);
+        let _ = unit.update(IliaMessage::EntryUpdate(String::new()));
+        assert_eq!(unit.state.marked, ["Profile Manager"]);
+    }
+
+    #[test]
+    fn test_confirmation_before_taking() {
+        let key = |named| IliaMessage::KeyEvent(Key::Named(named), Modifiers::empty());
+        let mut unit = loaded::<&str>(&[]);
+        let _ = unit.update(IliaMessage::ModelLoaded(vec![TestItem {
+            title: String::from("firefox"),
+            confirmation: Some(String::from("End firefox (412)?")),
+            group: None,
+        }]));
+        let _ = unit.update(key(Named::Enter));
+        assert!(unit.state.busy.is_none());
+        assert_eq!(
+            unit.view_tree().confirmation.as_deref(),
+            Some("End firefox (412)?")
+        );
+        // Going back leaves the item untaken
+        let _ = unit.update(key(Named::Escape));
+        assert_eq!(unit.view_tree().confirmation, None);
+        assert!(unit.state.busy.is_none());
+        let _ = unit.update(key(Named::Enter));
+        let _ = unit.update(key(Named::Enter));
+        assert_eq!(unit.state.busy.as_ref().map(|(index, _)| *index), Some(0));
+    }
+
+    #[test]
+    fn test_section_headers() {
+        let down = IliaMessage::KeyEvent(Key::Named(Named::ArrowDown), Modifiers::empty());
+        let mut unit = loaded::<&str>(&[]);
+        let item = |title: &str, group: &str| TestItem {
+            title: String::from(title),
+            confirmation: None,
+            group: Some(String::from(group)),
+        };
+        let _ = unit.update(IliaMessage::ModelLoaded(vec![
+            item("Firefox", "Favorites"),
+            item("Files", "Favorites"),
+            item("Terminal", "Applications"),
+        ]));
+        let headers: Vec<_> = unit
+            .view_tree()
+            .rows
+            .iter()
+            .map(|row| row.header.clone())
+            .collect();
+        assert_eq!(
+            headers,
+            [
+                Some(String::from("Favorites")),
+                None,
+                Some(String::from("Applications"))
+            ]
+        );
+        // Headers are not rows, so moving down goes from item to item
+        let _ = unit.update(down.clone());
+        let _ = unit.update(down);
+        assert_eq!(unit.selected_entry().unwrap().title, "Terminal");
+    }
+
+    #[test]
+    fn test_item_actions_in_palette() {
+        let open = IliaMessage::KeyEvent(Key::Named(Named::Enter), Modifiers::CTRL);
+        let labels = |unit: &Ilia<TestItem>| -> Vec<String> {
+            unit.view_tree()
+                .rows
+                .iter()
+                .map(|row| row.label.clone())
+                .collect()
+        };
+        let mut unit = with_provider(TestProvider {
+            acts: true,
+            ..TestProvider::new(&APP_TITLES)
+        });
+        let _ = unit.navigate_items(1);
+        let _ = unit.update(open.clone());
+        assert_eq!(unit.state.entry, ">");
+        assert_eq!(labels(&unit)[0], "Rename Files");
+        // The actions stay those of the item as the palette is filtered
+        let _ = unit.update(IliaMessage::EntryUpdate(String::from(">ren")));
+        assert_eq!(labels(&unit), ["Rename Files"]);
+        let _ = unit.update(IliaMessage::ExecuteSelected());
+        assert_eq!(unit.state.busy.as_ref().map(|(index, _)| *index), Some(0));
+
+        // Items without actions open the palette on ilia's own commands
+        let mut unit = loaded(&APP_TITLES);
+        let _ = unit.update(open);
+        assert_eq!(labels(&unit)[0], "Switch theme");
+    }
+
+    #[test]
+    fn test_kiosk_stays_open() {
+        let escape = IliaMessage::KeyEvent(Key::Named(Named::Escape), Modifiers::empty());
+        let mut unit = loaded(&APP_TITLES);
+        unit.flags.kiosk = true;
+        let _ = unit.update(IliaMessage::EntryUpdate(String::from("fi")));
+        let _ = unit.update(escape);
+        assert_eq!(unit.state.entry, "");
+        let _ = unit.update(IliaMessage::Dismissed);
+        let _ = unit.update(IliaMessage::EntryUpdate(String::from(">")));
+        assert!(!unit
+            .palette_entries()
+            .iter()
+            .any(|action| action.id == "open-config"));
+    }
+
+    #[test]
+    fn test_favorites_lead_empty_query() {
+        let favorite = IliaMessage::KeyEvent(Key::Character("d".into()), Modifiers::CTRL);
+        let mut unit = loaded(&APP_TITLES);
+        let _ = unit.navigate_items(2);
+        let _ = unit.update(favorite.clone());
+        assert_eq!(filtered_titles(&unit)[0], APP_TITLES[2]);
+        assert_eq!(unit.selected_entry().unwrap().title, APP_TITLES[2]);
+        assert_eq!(unit.pinned_rows(), 1);
+        let _ = unit.update(favorite);
+        assert_eq!(filtered_titles(&unit), APP_TITLES);
+        assert_eq!(unit.pinned_rows(), 0);
+    }
+
+    #[test]
+    fn test_matches_follow_changes() {
+        let mut unit = loaded(&["Firefox", "Files"]);
+        let _ = unit.update(IliaMessage::EntryUpdate(String::from("fi")));
+        assert_eq!(filtered_titles(&unit), ["Firefox", "Files"]);
+        // Matched once, until the items change
+        assert!(Rc::ptr_eq(&unit.matches(), &unit.matches()));
+
+        let updated = items(&["Firmware"]).remove(0);
+        let _ = unit.update(IliaMessage::ItemUpdated(1, updated));
+        assert_eq!(filtered_titles(&unit), ["Firefox", "Firmware"]);
+        let _ = unit.update(IliaMessage::EntryUpdate(String::from("Fil")));
+        assert!(filtered_titles(&unit).is_empty());
+        let _ = unit.toggle_case_sensitivity();
+        let _ = unit.update(IliaMessage::EntryUpdate(String::from("fir")));
+        assert!(filtered_titles(&unit).is_empty());
+        let _ = unit.toggle_case_sensitivity();
+        assert_eq!(filtered_titles(&unit), ["Firefox", "Firmware"]);
+    }
+
+    #[test]
+    fn test_action_takes_items() {
+        let mut unit = with_provider(TestProvider {
+            acts: true,
+            ..TestProvider::new(&APP_TITLES)
+        });
+        unit.flags.action = Some(String::from("rename"));
+        let _ = unit.update(IliaMessage::ExecuteSelected());
+        assert!(unit.state.busy.is_some());
+
+        // Items without the action are not taken
+        let mut unit = with_provider(TestProvider {
+            acts: true,
+            ..TestProvider::new(&APP_TITLES)
+        });
+        unit.flags.action = Some(String::from("kill"));
+        let _ = unit.update(IliaMessage::ExecuteSelected());
+        assert!(unit.state.busy.is_none());
+        assert!(unit.state.error.is_some());
+    }
+
+    #[test]
+    fn test_load_warning_shown() {
+        #[derive(Debug)]
+        struct PartialProvider;
+
+        impl ItemProvider for PartialProvider {
+            type Item = TestItem;
+
+            fn load(&self) -> anyhow::Result<Vec<TestItem>> {
+                Ok(items(&APP_TITLES))
+            }
+
+            fn load_warning(&self) -> Option<String> {
+                Some(String::from("docker failed"))
+            }
+        }
+
+        let configuration =
+            IliaConfiguration::new(Arc::new(PartialProvider), String::new(), Config::default());
+        let (mut unit, _) = Ilia::new(configuration);
+        let _ = unit.update(IliaMessage::ModelLoaded(items(&APP_TITLES)));
+        assert_eq!(unit.state.error.as_deref(), Some("docker failed"));
+        assert_eq!(filtered_titles(&unit), APP_TITLES);
+    }
+
+    #[test]
+    fn test_timeout_takes_selected() {
+        let mut unit = loaded(&APP_TITLES);
+        unit.flags.timeout = Some(Timeout {
+            after: Duration::ZERO,
+            take_selected: true,
+        });
+        unit.state.deadline = Some(Instant::now() + Duration::from_secs(10));
+        let _ = unit.update(IliaMessage::TimeoutTicked);
+        assert!(unit.state.busy.is_none());
+        unit.state.deadline = Some(Instant::now());
+        let _ = unit.update(IliaMessage::TimeoutTicked);
+        assert_eq!(unit.state.busy.as_ref().map(|(index, _)| *index), Some(0));
+    }
+
+    #[test]
+    fn test_countdown() {
+        let mut unit = loaded(&APP_TITLES);
+        assert_eq!(unit.view_tree().countdown, None);
+        unit.flags.timeout = Some(Timeout {
+            after: Duration::from_secs(20),
+            take_selected: false,
+        });
+        unit.state.deadline = Some(Instant::now() + Duration::from_millis(9_500));
+        let countdown = unit.view_tree().countdown.unwrap();
+        assert_eq!(countdown.label, "closing in 10 s");
+        assert!(countdown.left > 0.45 && countdown.left <= 0.475);
+    }
+
+    #[test]
+    fn test_key_press_stops_timeout() {
+        let mut unit = loaded(&APP_TITLES);
+        unit.state.deadline = Some(Instant::now());
+        let _ = unit.update(IliaMessage::KeyEvent(
+            Key::Named(Named::ArrowDown),
+            Modifiers::empty(),
+        ));
+        assert_eq!(unit.state.deadline, None);
+    }
+
+    #[test]
+    fn test_details_toggle() {
+        let f2 = IliaMessage::KeyEvent(Key::Named(Named::F2), Modifiers::empty());
+        let mut unit = loaded(&["Firefox"]);
+        assert!(unit.view_tree().details.is_empty());
+        let _ = unit.update(f2.clone());
+        assert_eq!(
+            unit.view_tree().details,
+            [(String::from("Length"), String::from("7"))]
+        );
+        let _ = unit.update(f2);
+        assert!(unit.view_tree().details.is_empty());
+    }
+
+    #[test]
+    fn test_tab_completes_selected_title() {
+        let tab = IliaMessage::KeyEvent(Key::Named(Named::Tab), Modifiers::empty());
+        let mut unit = loaded(&APP_TITLES);
+        let _ = unit.update(IliaMessage::EntryUpdate(String::from("vsc")));
+        let _ = unit.update(tab);
+        assert_eq!(unit.state.entry, "Visual Studio Code - Insiders");
+        assert_eq!(
+            unit.selected_entry().unwrap().title,
+            "Visual Studio Code - Insiders"
+        );
+    }
+
+    #[test]
+    fn test_loading_until_loaded() {
+        let (mut unit, _) = Ilia::new(loaded::<&str>(&[]).flags);
+        assert_eq!(unit.view_tree().status.as_deref(), Some("loading…"));
+        let _ = unit.update(IliaMessage::ModelLoaded(items(&["Firefox"])));
+        assert_eq!(unit.view_tree().status, None);
+    }
+
+    #[test]
+    fn test_navigate_wraps_around() {
+        let key = |named| IliaMessage::KeyEvent(Key::Named(named), Modifiers::empty());
+        let mut unit = loaded(&["a", "b", "c"]);
+        unit.flags.config.wrap_around = true;
+        let _ = unit.update(key(Named::ArrowUp));
+        assert_eq!(unit.state.selected_index, 2);
+        let _ = unit.update(key(Named::ArrowDown));
+        assert_eq!(unit.state.selected_index, 0);
+    }
+
+    #[test]
+    fn test_navigate_empty_list() {
+        let mut unit = loaded::<&str>(&[]);
+        let _ = unit.navigate_items(1);
+        let _ = unit.navigate_items(-1);
+        assert_eq!(unit.state.selected_index, 0);
+    }
+
+    #[test]
+    fn test_navigate_extreme_deltas() {
+        let mut unit = loaded(&["a", "b", "c"]);
+        let _ = unit.navigate_items(1);
+        let _ = unit.navigate_items(isize::MAX);
+        let _ = unit.navigate_items(isize::MIN);
+        assert_eq!(unit.state.selected_index, 1);
+    }
+
+    proptest! {
+        #[test]
+        fn test_selection_within_filtered(titles in titles(), ops in proptest::collection::vec(op(), 0..60)) {
+            let mut unit = loaded(&titles);
+            for op in &ops {
+                apply(&mut unit, op);
+                let count = unit.filtered_items().count();
+                prop_assert!(unit.state.selected_index < count.max(1));
+                prop_assert_eq!(unit.selected_entry().is_some(), count > 0);
+            }
+        }
+
+        #[test]
+        fn test_filter_stable_under_append_and_delete(titles in titles(), query in "[a-e]{0,3}", c in proptest::char::range('a', 'e')) {
+            let mut unit = loaded(&titles);
+            let _ = unit.update(IliaMessage::EntryUpdate(query.clone()));
+            let before = filtered_titles(&unit);
+
+            apply(&mut unit, &Op::Type(c));
+            let narrowed = filtered_titles(&unit);
+            prop_assert!(narrowed.iter().all(|title| before.contains(title)));
+
+            apply(&mut unit, &Op::Delete);
+            prop_assert_eq!(filtered_titles(&unit), before);
+        }
+
+        #[test]
+        fn test_narrowed_filter_matches_full_filter(titles in titles(), typed in "[a-eA-E ]{0,6}") {
+            let mut unit = loaded(&titles);
+            for c in typed.chars() {
+                apply(&mut unit, &Op::Type(c));
+                let narrowed = filtered_titles(&unit);
+                unit.forget_matches();
+                prop_assert_eq!(narrowed, filtered_titles(&unit));
+            }
+        }
+    }
+}
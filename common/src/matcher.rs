@@ -0,0 +1,207 @@
+//! How the query picks and orders items, swappable by front-ends which match differently
+use std::fmt::Debug;
+use std::ops::Range;
+use std::sync::{Mutex, MutexGuard};
+
+use regex::{Error, Regex};
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+use crate::fuzzy;
+
+/// Entry prefix which matches the rest of the query as a regular expression
+pub const REGEX_PREFIX: char = '/';
+
+/// A strategy for matching the query against item titles
+pub trait Matcher: Debug + Send + Sync {
+    /// How well the item matches the query, higher ranking first, or None to filter it out
+    fn score(&self, query: &str, item: &str) -> Option<u32>;
+
+    /// Byte ranges of the item to emphasize as matching the query.  None by default.
+    fn highlights(&self, _query: &str, _item: &str) -> Vec<Range<usize>> {
+        vec![]
+    }
+}
+
+/// fzf-style matching of the query's characters in order, the default
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FuzzyMatcher {
+    pub case_sensitive: bool,
+}
+
+impl Matcher for FuzzyMatcher {
+    fn score(&self, query: &str, item: &str) -> Option<u32> {
+        fuzzy::score(item, query, self.case_sensitive)
+            .map(|score| u32::try_from(score.max(0)).unwrap_or(u32::MAX))
+    }
+
+    fn highlights(&self, query: &str, item: &str) -> Vec<Range<usize>> {
+        fuzzy::match_ranges(item, query, self.case_sensitive)
+    }
+}
+
+/// Matching of the query as one piece anywhere in the item, earlier matches ranking first.  Both
+/// are decomposed first, so composed and decomposed forms of a character are equal.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SubstringMatcher {
+    pub case_sensitive: bool,
+}
+
+impl SubstringMatcher {
+    fn find(&self, query: &str, item: &str) -> Option<Range<usize>> {
+        let case_sensitive = self.case_sensitive;
+        let fold = move |c: char| -> Vec<char> {
+            if case_sensitive {
+                vec![c]
+            } else {
+                c.to_lowercase().collect()
+            }
+        };
+        // Compare decomposed characters, keeping byte positions in the original item
+        let query: Vec<char> = query.nfkd().flat_map(fold).collect();
+        let chars: Vec<(usize, char)> = item
+            .char_indices()
+            .flat_map(|(index, c)| {
+                std::iter::once(c)
+                    .nfkd()
+                    .flat_map(fold)
+                    .map(move |c| (index, c))
+            })
+            .collect();
+        if query.is_empty() {
+            return Some(0..0);
+        }
+        chars
+            .windows(query.len())
+            .enumerate()
+            .find(|(offset, window)| {
+                // A match may not end between a letter and its combining marks
+                window.iter().map(|(_, c)| *c).eq(query.iter().copied())
+                    && chars
+                        .get(offset + window.len())
+                        .is_none_or(|(_, next)| !is_combining_mark(*next))
+            })
+            .map(|(_, window)| {
+                let (start, _) = window[0];
+                let (last, _) = window[window.len() - 1];
+                start..last + item[last..].chars().next().map_or(0, char::len_utf8)
+            })
+    }
+}
+
+impl Matcher for SubstringMatcher {
+    fn score(&self, query: &str, item: &str) -> Option<u32> {
+        self.find(query, item)
+            .map(|range| u32::MAX - u32::try_from(range.start).unwrap_or(u32::MAX))
+    }
+
+    fn highlights(&self, query: &str, item: &str) -> Vec<Range<usize>> {
+        self.find(query, item)
+            .filter(|range| !range.is_empty())
+            .into_iter()
+            .collect()
+    }
+}
+
+/// Matching of the query as a regular expression, such as `^code.*insiders`, earlier matches
+/// ranking first.  Each pattern is compiled once, on its first use.
+#[derive(Debug, Default)]
+pub struct RegexMatcher {
+    compiled: Mutex<Option<(String, Result<Regex, Error>)>>,
+}
+
+impl RegexMatcher {
+    // The pattern, compiled unless it is the last one compiled
+    fn compile(&self, pattern: &str) -> MutexGuard<'_, Option<(String, Result<Regex, Error>)>> {
+        let mut compiled = self.compiled.lock().unwrap_or_else(|err| err.into_inner());
+        if compiled
+            .as_ref()
+            .is_none_or(|(compiled, _)| compiled != pattern)
+        {
+            *compiled = Some((pattern.to_string(), Regex::new(pattern)));
+        }
+        compiled
+    }
+
+    fn with_regex<R>(&self, pattern: &str, f: impl FnOnce(&Regex) -> Option<R>) -> Option<R> {
+        match self.compile(pattern).as_ref() {
+            Some((_, Ok(regex))) => f(regex),
+            _ => None,
+        }
+    }
+
+    /// Why the pattern is not a valid regular expression, if it isn't
+    pub fn error(&self, pattern: &str) -> Option<String> {
+        match self.compile(pattern).as_ref() {
+            Some((_, Err(err))) => Some(err.to_string()),
+            _ => None,
+        }
+    }
+}
+
+impl Matcher for RegexMatcher {
+    fn score(&self, query: &str, item: &str) -> Option<u32> {
+        self.with_regex(query, |regex| regex.find(item))
+            .map(|found| u32::MAX - u32::try_from(found.start()).unwrap_or(u32::MAX))
+    }
+
+    fn highlights(&self, query: &str, item: &str) -> Vec<Range<usize>> {
+        self.with_regex(query, |regex| {
+            Some(
+                regex
+                    .find_iter(item)
+                    .map(|found| found.range())
+                    .filter(|range| !range.is_empty())
+                    .collect(),
+            )
+        })
+        .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_matcher() {
+        let matcher = FuzzyMatcher::default();
+        assert!(matcher.score("ffx", "Firefox").is_some());
+        assert!(matcher.score("xff", "Firefox").is_none());
+        assert_eq!(matcher.highlights("ffx", "Firefox"), [0..1, 4..5, 6..7]);
+    }
+
+    #[test]
+    fn test_substring_matcher() {
+        let matcher = SubstringMatcher::default();
+        assert!(matcher.score("ffx", "Firefox").is_none());
+        assert!(matcher.score("fi", "Files") > matcher.score("fi", "Profile Manager"));
+        assert_eq!(matcher.highlights("FOX", "Firefox").first(), Some(&(4..7)));
+        assert_eq!(matcher.highlights("ÖSS", "Größe").first(), None);
+        assert_eq!(matcher.highlights("Öß", "Größe").first(), Some(&(2..6)));
+        // Composed and decomposed forms are equal, but marks are not ignored
+        assert_eq!(
+            matcher.highlights("fé", "Cafe\u{301}").first(),
+            Some(&(2..6))
+        );
+        assert!(matcher.score("cafe\u{301}", "Café").is_some());
+        assert!(matcher.score("cafe", "Cafe\u{301}").is_none());
+        assert!(SubstringMatcher {
+            case_sensitive: true
+        }
+        .score("FOX", "Firefox")
+        .is_none());
+    }
+
+    #[test]
+    fn test_regex_matcher() {
+        let matcher = RegexMatcher::default();
+        let title = "Visual Studio Code - Insiders";
+        assert!(matcher.score("(?i)^visual.*insiders", title).is_some());
+        assert!(matcher.score("^Code", title).is_none());
+        assert_eq!(matcher.highlights("[VS]", title), [0..1, 7..8]);
+        assert!(matcher.error("^Code").is_none());
+        assert!(matcher.error("(unclosed").is_some());
+        assert!(matcher.score("(unclosed", title).is_none());
+    }
+}
@@ -0,0 +1,70 @@
+//! The command palette, listing the actions of the selected item and ilia's own, registered by id
+//! as items register theirs
+use iced::Task;
+use iced_runtime::futures::MaybeSend;
+
+use crate::i18n::tr;
+use crate::{Action, ActionFn, Ilia, IliaMessage, ItemDescriptor};
+
+/// Entry prefix which switches the list from items to actions
+pub const PALETTE_PREFIX: char = '>';
+
+/// One of ilia's own operations on the launcher
+pub(crate) type Operation<T> = fn(&mut Ilia<T>) -> Task<IliaMessage<T>>;
+
+/// An action listed in the palette
+pub(crate) struct PaletteAction<T: MaybeSend + ItemDescriptor> {
+    /// Stable identifier, as of an item's [`Action`], such as "switch-theme"
+    pub id: &'static str,
+    /// Human readable name of the action, which the palette query matches
+    pub name: String,
+    pub run: Run<T>,
+}
+
+/// What a palette action performs
+pub(crate) enum Run<T: MaybeSend + ItemDescriptor> {
+    /// One of ilia's own operations
+    Launcher(Operation<T>),
+    /// An action of the item selected as the palette opened
+    Item(ActionFn<T>),
+}
+
+impl<T: MaybeSend + ItemDescriptor> From<Action<T>> for PaletteAction<T> {
+    fn from(action: Action<T>) -> Self {
+        PaletteAction {
+            id: action.id,
+            name: action.name,
+            run: Run::Item(action.run),
+        }
+    }
+}
+
+/// ilia's own operations, in the order listed in the palette after the item's actions
+pub(crate) fn launcher_actions<T>() -> Vec<PaletteAction<T>>
+where
+    T: MaybeSend + Clone + ItemDescriptor + 'static,
+{
+    let operations: [(&'static str, &str, Operation<T>); 5] = [
+        ("switch-theme", "command-switch-theme", Ilia::switch_theme),
+        (
+            "toggle-case-sensitivity",
+            "command-toggle-case-sensitivity",
+            Ilia::toggle_case_sensitivity,
+        ),
+        (
+            "toggle-details",
+            "command-toggle-details",
+            Ilia::toggle_details,
+        ),
+        ("reload", "command-reload-items", Ilia::reload),
+        ("open-config", "command-open-config", Ilia::edit_config),
+    ];
+    operations
+        .into_iter()
+        .map(|(id, message, run)| PaletteAction {
+            id,
+            name: tr(message),
+            run: Run::Launcher(run),
+        })
+        .collect()
+}
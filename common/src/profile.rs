@@ -0,0 +1,67 @@
+//! Timings of the phases of startup, printed to stderr with `--profile` so reports of slowness
+//! carry numbers.  Each phase is printed once, when it first ends, as a line such as
+//! `ilia-profile phase=model_load at_ms=41.7 took_ms=38.2`.
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// When profiling started, unset while not profiling
+static STARTED: OnceLock<Instant> = OnceLock::new();
+
+/// The phases printed so far
+static PRINTED: Mutex<Vec<&'static str>> = Mutex::new(vec![]);
+
+/// Print the time each phase of startup ends, counted from now
+pub fn profile_startup() {
+    let _ = STARTED.set(Instant::now());
+}
+
+/// Whether phases are printed, so they are only timed then
+pub(crate) fn enabled() -> bool {
+    STARTED.get().is_some()
+}
+
+/// The phase ended now, having taken `took` if it was timed on its own
+pub(crate) fn phase(name: &'static str, took: Option<Duration>) {
+    let Some(started) = STARTED.get() else {
+        return;
+    };
+    let mut printed = PRINTED.lock().unwrap_or_else(|err| err.into_inner());
+    if printed.contains(&name) {
+        return;
+    }
+    printed.push(name);
+    eprintln!("{}", line(name, started.elapsed(), took));
+}
+
+fn line(name: &str, at: Duration, took: Option<Duration>) -> String {
+    let mut line = format!("ilia-profile phase={} at_ms={:.1}", name, millis(at));
+    if let Some(took) = took {
+        line.push_str(&format!(" took_ms={:.1}", millis(took)));
+    }
+    line
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line() {
+        assert_eq!(
+            line("first_frame", Duration::from_micros(52_340), None),
+            "ilia-profile phase=first_frame at_ms=52.3"
+        );
+        assert_eq!(
+            line(
+                "first_filter",
+                Duration::from_millis(900),
+                Some(Duration::from_micros(4_020))
+            ),
+            "ilia-profile phase=first_filter at_ms=900.0 took_ms=4.0"
+        );
+    }
+}
@@ -0,0 +1,61 @@
+//! The query typed into the entry, split into the text items must match and the terms they must
+//! not, so "term !kitty" finds terminals other than kitty
+use std::borrow::Cow;
+
+/// Prefix of a term excluding the items it matches
+pub const EXCLUDE_PREFIX: char = '!';
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query<'a> {
+    /// The query without its excluded terms, as typed when it has none
+    pub include: Cow<'a, str>,
+    /// Terms, without their prefix, matching the items to leave out
+    pub exclude: Vec<&'a str>,
+}
+
+/// Split the query on its excluded terms.  A lone `!` excludes nothing, as when one is being typed.
+pub fn parse(query: &str) -> Query<'_> {
+    if !query
+        .split_whitespace()
+        .any(|term| term.starts_with(EXCLUDE_PREFIX))
+    {
+        return Query {
+            include: Cow::Borrowed(query),
+            exclude: vec![],
+        };
+    }
+
+    let (excluded, included): (Vec<&str>, Vec<&str>) = query
+        .split_whitespace()
+        .partition(|term| term.starts_with(EXCLUDE_PREFIX));
+    Query {
+        include: Cow::Owned(included.join(" ")),
+        exclude: excluded
+            .into_iter()
+            .map(|term| &term[EXCLUDE_PREFIX.len_utf8()..])
+            .filter(|term| !term.is_empty())
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let query = parse("term !kitty");
+        assert_eq!(query.include, "term");
+        assert_eq!(query.exclude, ["kitty"]);
+
+        let query = parse("!kitty  visual  !code ");
+        assert_eq!(query.include, "visual");
+        assert_eq!(query.exclude, ["kitty", "code"]);
+
+        // Queries without exclusions are kept as typed
+        assert!(matches!(parse(" term ").include, Cow::Borrowed(" term ")));
+        assert!(parse("term !").exclude.is_empty());
+        assert_eq!(parse("term !").include, "term");
+        assert_eq!(parse("wi!fi").include, "wi!fi");
+    }
+}
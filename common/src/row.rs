@@ -0,0 +1,420 @@
+//! The widget drawing one row of the list, shared by every front-end so rows look and behave the
+//! same whatever the provider puts in them
+use std::ops::Range;
+
+use iced::alignment::Horizontal;
+use iced::font::Weight;
+use iced::widget::button::{primary, text};
+use iced::widget::text::Span;
+use iced::widget::{button, mouse_area, rich_text, span, Column, Row, Text};
+use iced::{Color, Element, Font, Length, Theme};
+
+/// Opacity of the row text while a reload is pending
+const STALE_ALPHA: f32 = 0.6;
+/// Characters of the title shown before it is cut off with an ellipsis
+const DEFAULT_MAX_CHARS: usize = 40;
+/// Size of the subtitle and badge text, smaller than the title
+const DETAIL_TEXT_SIZE: u16 = 12;
+/// Opacity of the subtitle text, dimmer than the title
+const SUBTITLE_ALPHA: f32 = 0.7;
+/// Drawn before the title of marked rows
+const MARK: &str = "✓";
+
+/// Separators before the name of the app at the end of window titles, such as
+/// "Page title — Mozilla Firefox"
+const SUFFIX_SEPARATORS: [&str; 4] = [" — ", " – ", " - ", " | "];
+
+/// Where a title too long for its row is cut
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Ellipsis {
+    /// Cut off its end
+    #[default]
+    End,
+    /// Cut from its middle, keeping its end, such as the app name window titles end with
+    Middle,
+}
+
+/// A selectable list row with a title, and optionally an icon, subtitle, badge and highlighted
+/// spans of the title
+pub struct ItemRow<'a, Message> {
+    title: String,
+    subtitle: Option<String>,
+    badge: Option<String>,
+    shortcut: Option<String>,
+    icon: Option<Element<'a, Message>>,
+    highlights: Vec<Range<usize>>,
+    highlight_color: Option<Color>,
+    selected: bool,
+    marked: bool,
+    stale: bool,
+    max_chars: usize,
+    ellipsis: Ellipsis,
+    align_x: Horizontal,
+    on_press: Option<Message>,
+    on_hover: Option<Message>,
+}
+
+impl<'a, Message: Clone + 'static> ItemRow<'a, Message> {
+    pub fn new(title: impl Into<String>) -> Self {
+        ItemRow {
+            title: title.into(),
+            subtitle: None,
+            badge: None,
+            shortcut: None,
+            icon: None,
+            highlights: vec![],
+            highlight_color: None,
+            selected: false,
+            marked: false,
+            stale: false,
+            max_chars: DEFAULT_MAX_CHARS,
+            ellipsis: Ellipsis::End,
+            align_x: Horizontal::Left,
+            on_press: None,
+            on_hover: None,
+        }
+    }
+
+    /// A secondary line under the title, such as a description
+    pub fn subtitle(mut self, subtitle: Option<String>) -> Self {
+        self.subtitle = subtitle;
+        self
+    }
+
+    /// A short tag drawn at the end of the row, such as "[X]" for XWayland windows
+    pub fn badge(mut self, badge: Option<String>) -> Self {
+        self.badge = badge;
+        self
+    }
+
+    /// A key hint drawn before everything else, such as "1" for the row Alt+1 takes
+    pub fn shortcut(mut self, shortcut: Option<String>) -> Self {
+        self.shortcut = shortcut;
+        self
+    }
+
+    /// Any widget drawn at the start of the row, such as an image of the app icon
+    pub fn icon(mut self, icon: impl Into<Element<'a, Message>>) -> Self {
+        self.icon = Some(icon.into());
+        self
+    }
+
+    /// Byte ranges of the title to emphasize, such as the part matching the query
+    pub fn highlights(mut self, highlights: Vec<Range<usize>>) -> Self {
+        self.highlights = highlights;
+        self
+    }
+
+    /// Color of the emphasized spans, such as the theme's primary color, on top of their bold
+    /// weight.  Not applied to the selected row, whose background is already that color.
+    pub fn highlight_color(mut self, color: Color) -> Self {
+        self.highlight_color = Some(color);
+        self
+    }
+
+    /// Draw the row with the primary style
+    pub fn selected(mut self, selected: bool) -> Self {
+        self.selected = selected;
+        self
+    }
+
+    /// Check the row as marked to be taken with others
+    pub fn marked(mut self, marked: bool) -> Self {
+        self.marked = marked;
+        self
+    }
+
+    /// Gray out the row, while a reload is pending
+    pub fn stale(mut self, stale: bool) -> Self {
+        self.stale = stale;
+        self
+    }
+
+    /// Characters of the title to show before cutting it off with an ellipsis
+    pub fn max_chars(mut self, max_chars: usize) -> Self {
+        self.max_chars = max_chars;
+        self
+    }
+
+    /// Where the title is cut when it is longer than `max_chars`
+    pub fn ellipsis(mut self, ellipsis: Ellipsis) -> Self {
+        self.ellipsis = ellipsis;
+        self
+    }
+
+    /// Where the text sits within the row, to the right for right to left layouts
+    pub fn align_x(mut self, align_x: Horizontal) -> Self {
+        self.align_x = align_x;
+        self
+    }
+
+    /// The message sent when the row is clicked
+    pub fn on_press(mut self, message: Message) -> Self {
+        self.on_press = Some(message);
+        self
+    }
+
+    /// The message sent as the pointer moves over the row
+    pub fn on_hover(mut self, message: Message) -> Self {
+        self.on_hover = Some(message);
+        self
+    }
+}
+
+impl<'a, Message: Clone + 'static> From<ItemRow<'a, Message>> for Element<'a, Message> {
+    fn from(row: ItemRow<'a, Message>) -> Self {
+        let ItemRow {
+            title,
+            subtitle,
+            badge,
+            shortcut,
+            icon,
+            highlights,
+            highlight_color,
+            selected,
+            marked,
+            stale,
+            max_chars,
+            ellipsis,
+            align_x,
+            on_press,
+            on_hover,
+        } = row;
+
+        let (title, highlights) = match ellipsis {
+            Ellipsis::End => ellipsize(&title, &highlights, max_chars),
+            Ellipsis::Middle => ellipsize_middle(&title, &highlights, max_chars),
+        };
+        let highlight_color = highlight_color.filter(|_| !selected).map(|color| {
+            if stale {
+                color.scale_alpha(STALE_ALPHA)
+            } else {
+                color
+            }
+        });
+        let title = rich_text(title_spans(title, &highlights, highlight_color))
+            .width(Length::Fill)
+            .align_x(align_x);
+        let text_column = Column::new()
+            .push(title)
+            .push_maybe(subtitle.map(|subtitle| {
+                Text::new(subtitle)
+                    .size(DETAIL_TEXT_SIZE)
+                    .width(Length::Fill)
+                    .align_x(align_x)
+                    .style(move |theme: &Theme| {
+                        let style = if selected {
+                            primary(theme, button::Status::Active)
+                        } else {
+                            text(theme, button::Status::Active)
+                        };
+                        let alpha = if stale {
+                            SUBTITLE_ALPHA * STALE_ALPHA
+                        } else {
+                            SUBTITLE_ALPHA
+                        };
+                        iced::widget::text::Style {
+                            color: Some(style.text_color.scale_alpha(alpha)),
+                        }
+                    })
+            }));
+
+        let content = Row::new()
+            .push_maybe(shortcut.map(|shortcut| Text::new(shortcut).size(DETAIL_TEXT_SIZE)))
+            .push_maybe(marked.then(|| Text::new(MARK)))
+            .push_maybe(icon)
+            .push(text_column)
+            .push_maybe(badge.map(|badge| Text::new(badge).size(DETAIL_TEXT_SIZE)))
+            .spacing(8);
+
+        let button = button(content)
+            .style(move |theme, status| {
+                let style = if selected {
+                    primary(theme, status)
+                } else {
+                    text(theme, status)
+                };
+                // Gray out the old list while waiting on a reload
+                if stale {
+                    button::Style {
+                        text_color: style.text_color.scale_alpha(STALE_ALPHA),
+                        ..style
+                    }
+                } else {
+                    style
+                }
+            })
+            .width(Length::Fill)
+            .on_press_maybe(on_press);
+        match on_hover {
+            Some(message) => mouse_area(button).on_move(move |_| message.clone()).into(),
+            None => button.into(),
+        }
+    }
+}
+
+// Split the title into plain and emphasized spans
+fn title_spans<Link>(
+    title: String,
+    highlights: &[Range<usize>],
+    color: Option<Color>,
+) -> Vec<Span<'static, Link, Font>> {
+    let bold = Font {
+        weight: Weight::Bold,
+        ..Font::DEFAULT
+    };
+    let mut spans = vec![];
+    let mut end = 0;
+    for highlight in highlights {
+        if highlight.start > end {
+            spans.push(span(title[end..highlight.start].to_string()));
+        }
+        spans.push(
+            span(title[highlight.clone()].to_string())
+                .font(bold)
+                .color_maybe(color),
+        );
+        end = highlight.end;
+    }
+    if end < title.len() || spans.is_empty() {
+        spans.push(span(title[end..].to_string()));
+    }
+    spans
+}
+
+// Shorten the title to at most `max_chars` characters, ending it with an ellipsis if it was
+// cut, and clip the highlighted ranges to what remains
+fn ellipsize(
+    title: &str,
+    highlights: &[Range<usize>],
+    max_chars: usize,
+) -> (String, Vec<Range<usize>>) {
+    let cut = match title.char_indices().nth(max_chars.saturating_sub(1)) {
+        Some((index, _)) if title[index..].chars().count() > 1 => index,
+        _ => return (title.to_string(), highlights.to_vec()),
+    };
+    let highlights = highlights
+        .iter()
+        .filter(|highlight| highlight.start < cut)
+        .map(|highlight| highlight.start..highlight.end.min(cut))
+        .collect();
+    (format!("{}…", &title[..cut]), highlights)
+}
+
+// Shorten the title to at most `max_chars` characters by cutting its middle for an ellipsis,
+// keeping the app name it ends with if that takes at most two thirds, else as much of the end as
+// of the start.  The highlighted ranges are clipped and moved along to what remains.
+fn ellipsize_middle(
+    title: &str,
+    highlights: &[Range<usize>],
+    max_chars: usize,
+) -> (String, Vec<Range<usize>>) {
+    let chars = title.chars().count();
+    if chars <= max_chars || max_chars < 3 {
+        return ellipsize(title, highlights, max_chars);
+    }
+    let kept = max_chars - 1;
+    let tail_chars = SUFFIX_SEPARATORS
+        .iter()
+        .filter_map(|separator| title.rfind(separator))
+        .max()
+        .map(|start| title[start..].chars().count())
+        .filter(|tail_chars| *tail_chars <= kept * 2 / 3)
+        .unwrap_or(kept / 2);
+    let byte_at = |char_index: usize| {
+        title
+            .char_indices()
+            .nth(char_index)
+            .map_or(title.len(), |(index, _)| index)
+    };
+    let head_end = byte_at(kept - tail_chars);
+    let tail_start = byte_at(chars - tail_chars);
+    let moved = head_end + '…'.len_utf8();
+    let highlights = highlights
+        .iter()
+        .flat_map(|highlight| {
+            let head = highlight.start..highlight.end.min(head_end);
+            let tail = highlight.start.max(tail_start) - tail_start + moved
+                ..highlight.end.saturating_sub(tail_start) + moved;
+            [head, tail]
+        })
+        .filter(|highlight| !highlight.is_empty())
+        .collect();
+    (
+        format!("{}…{}", &title[..head_end], &title[tail_start..]),
+        highlights,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ellipsize_short_title() {
+        assert_eq!(
+            ellipsize("Files", &[0..1, 3..5], 5),
+            (String::from("Files"), vec![0..1, 3..5])
+        );
+    }
+
+    #[test]
+    fn test_ellipsize_long_title() {
+        assert_eq!(
+            ellipsize("Visual Studio Code", &[0..3, 4..5, 7..13], 8),
+            (String::from("Visual …"), vec![0..3, 4..5])
+        );
+    }
+
+    #[test]
+    fn test_ellipsize_multibyte() {
+        assert_eq!(ellipsize("Größenänderung", &[], 4).0, "Grö…");
+    }
+
+    #[test]
+    fn test_ellipsize_middle_keeps_app_name() {
+        assert_eq!(
+            ellipsize_middle(
+                "How to cut a long title — Mozilla Firefox",
+                &[0..3, 20..30, 34..41],
+                30
+            ),
+            (
+                String::from("How to cut … — Mozilla Firefox"),
+                vec![0..3, 14..21, 25..32]
+            )
+        );
+    }
+
+    #[test]
+    fn test_ellipsize_middle_multibyte() {
+        // Window titles reach the row whole, however many bytes their characters take
+        assert_eq!(
+            ellipsize_middle("如何在终端里剪切很长的窗口标题 — Mozilla Firefox", &[], 30).0,
+            "如何在终端里剪切很长的… — Mozilla Firefox"
+        );
+    }
+
+    #[test]
+    fn test_ellipsize_middle_without_app_name() {
+        assert_eq!(
+            ellipsize_middle("abcdefghijklmnopqrstuvwxyz", &[], 9).0,
+            "abcd…wxyz"
+        );
+        assert_eq!(ellipsize_middle("Files", &[], 5).0, "Files");
+    }
+
+    #[test]
+    fn test_title_spans_cover_title() {
+        let spans = title_spans::<()>(
+            String::from("Profile Manager"),
+            &[0..2, 3..5],
+            Some(Color::WHITE),
+        );
+        let texts: Vec<&str> = spans.iter().map(|span| span.text.as_ref()).collect();
+        assert_eq!(texts, ["Pr", "o", "fi", "le Manager"]);
+        // Only the emphasized spans are colored
+        let colors: Vec<bool> = spans.iter().map(|span| span.color.is_some()).collect();
+        assert_eq!(colors, [true, false, true, false]);
+    }
+}
@@ -0,0 +1,36 @@
+//! The query of a cancelled launcher, kept briefly in `$XDG_RUNTIME_DIR` so reopening it right
+//! after a mistaken Escape restores what was typed
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+use anyhow::Context;
+
+// Location of the saved query of a session
+fn path(session: &str) -> Option<PathBuf> {
+    dirs::runtime_dir().map(|dir| dir.join("ilia").join(format!("{}.query", session)))
+}
+
+/// Keep the query of a session which is being cancelled
+pub fn save(session: &str, query: &str) -> anyhow::Result<()> {
+    let path = path(session).context("XDG_RUNTIME_DIR is not set")?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).context("Failed to create runtime dir")?;
+    }
+    std::fs::write(&path, query).context("Failed to save query")
+}
+
+/// Take the query a session saved less than `max_age` ago, if any.  A query is only restored once.
+pub fn restore(session: &str, max_age: Duration) -> Option<String> {
+    let path = path(session)?;
+    let modified = path
+        .metadata()
+        .and_then(|metadata| metadata.modified())
+        .ok()?;
+    let query = std::fs::read_to_string(&path).ok();
+    let _ = std::fs::remove_file(&path);
+
+    let age = SystemTime::now()
+        .duration_since(modified)
+        .unwrap_or_default();
+    query.filter(|_| age < max_age)
+}
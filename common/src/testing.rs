@@ -0,0 +1,143 @@
+//! Fixtures shared by the unit tests
+use std::sync::Arc;
+
+use crate::{Action, Config, Ilia, IliaConfiguration, IliaMessage, ItemDescriptor, ItemProvider};
+
+/// Titles of a typical, unsorted set of loaded apps
+pub const APP_TITLES: [&str; 5] = [
+    "Firefox",
+    "Files",
+    "Profile Manager",
+    "Terminal",
+    "Visual Studio Code - Insiders",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestItem {
+    pub title: String,
+    /// Asked before the item is taken
+    pub confirmation: Option<String>,
+    /// The section the item is listed in
+    pub group: Option<String>,
+}
+
+impl ItemDescriptor for TestItem {
+    fn title(&self) -> &str {
+        &self.title
+    }
+
+    fn exec(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn confirmation(&self) -> Option<String> {
+        self.confirmation.clone()
+    }
+
+    fn group(&self) -> Option<&str> {
+        self.group.as_deref()
+    }
+
+    fn details(&self) -> Vec<(&'static str, String)> {
+        vec![("Length", self.title.len().to_string())]
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct TestProvider {
+    pub titles: Vec<String>,
+    /// Offer to create an item titled after the query
+    pub creates: bool,
+    /// Take a submitted query as an item titled after it
+    pub submits: bool,
+    /// Let items be marked to take them together
+    pub marks: bool,
+    /// Offer an action renaming each item
+    pub acts: bool,
+}
+
+impl TestProvider {
+    /// A provider of the items of the given titles, offering nothing more
+    pub fn new<S: AsRef<str>>(titles: &[S]) -> Self {
+        TestProvider {
+            titles: titles
+                .iter()
+                .map(|title| title.as_ref().to_string())
+                .collect(),
+            ..Default::default()
+        }
+    }
+}
+
+impl ItemProvider for TestProvider {
+    type Item = TestItem;
+
+    fn load(&self) -> anyhow::Result<Vec<TestItem>> {
+        Ok(items(&self.titles))
+    }
+
+    fn create(&self, query: &str) -> Option<TestItem> {
+        self.creates.then(|| TestItem {
+            title: query.to_string(),
+            confirmation: None,
+            group: None,
+        })
+    }
+
+    fn submit(&self, query: &str) -> Option<TestItem> {
+        self.submits.then(|| TestItem {
+            title: query.to_string(),
+            confirmation: None,
+            group: None,
+        })
+    }
+
+    fn actions(&self, item: &TestItem) -> Vec<Action<TestItem>> {
+        if !self.acts {
+            return vec![];
+        }
+        vec![Action {
+            id: "rename",
+            name: format!("Rename {}", item.title),
+            run: Arc::new(|_| Ok(())),
+        }]
+    }
+
+    fn marks(&self) -> bool {
+        self.marks
+    }
+}
+
+pub fn items<S: AsRef<str>>(titles: &[S]) -> Vec<TestItem> {
+    titles
+        .iter()
+        .map(|title| TestItem {
+            title: title.as_ref().to_string(),
+            confirmation: None,
+            group: None,
+        })
+        .collect()
+}
+
+/// An app with the items of the given titles loaded
+pub fn loaded<S: AsRef<str>>(titles: &[S]) -> Ilia<TestItem> {
+    with_provider(TestProvider::new(titles))
+}
+
+/// An app with the items of the given provider loaded
+pub fn with_provider(provider: TestProvider) -> Ilia<TestItem> {
+    started(configured(provider))
+}
+
+/// The default configuration of an app listing the items of the given provider
+pub fn configured(provider: TestProvider) -> IliaConfiguration<TestItem> {
+    IliaConfiguration::new(Arc::new(provider), String::from("test"), Config::default())
+}
+
+/// An app of the given configuration with the items of its provider loaded
+pub fn started(configuration: IliaConfiguration<TestItem>) -> Ilia<TestItem> {
+    let items = configuration.provider.load().unwrap();
+    let (mut ilia, _) = Ilia::new(configuration);
+    let _ = ilia.update(IliaMessage::ModelLoaded(items));
+    ilia
+}
@@ -0,0 +1,30 @@
+//! Closing the launcher on its own once it has waited long enough for a selection, as boot menus
+//! offering a choice within 10 seconds do
+use std::time::Duration;
+
+use iced_runtime::futures::futures::channel::mpsc;
+use iced_runtime::futures::futures::Stream;
+
+/// How often the time left is checked
+const TICK: Duration = Duration::from_millis(100);
+
+/// How long the launcher waits for a selection, and what it does once time runs out.  A key
+/// press stops the countdown.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Timeout {
+    pub after: Duration,
+    /// Take the selected item rather than close without one
+    pub take_selected: bool,
+}
+
+/// A tick every `TICK` until the stream is dropped, from a thread of its own as the executor
+/// has no timers
+pub(crate) fn ticks() -> impl Stream<Item = ()> {
+    let (sender, receiver) = mpsc::unbounded();
+    std::thread::spawn(move || {
+        while sender.unbounded_send(()).is_ok() {
+            std::thread::sleep(TICK);
+        }
+    });
+    receiver
+}
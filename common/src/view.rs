@@ -0,0 +1,180 @@
+//! A plain description of the widgets `Ilia::view` renders, independent of `iced`, so the
+//! layout can be inspected and snapshot tested
+use std::ops::Range;
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+/// The complete widget tree of the launcher window
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ViewTree {
+    /// A paragraph above the entry
+    pub message: Option<String>,
+    /// The query text entry, unless it is hidden
+    pub entry: Option<EntryView>,
+    /// A small status line under the entry, such as the reload indicator
+    pub status: Option<String>,
+    /// The time left before the timeout, while it runs
+    pub countdown: Option<CountdownView>,
+    /// A banner explaining the last failure
+    pub error: Option<String>,
+    /// A question about the item about to be taken, asked in place of the list
+    pub confirmation: Option<String>,
+    /// Rows leading the list for other reasons than matching, such as an alias's item, divided
+    /// from the rest in a single column
+    pub pinned: usize,
+    /// Rows of the list above the drawn ones, out of view and drawn as space of their height
+    pub rows_before: usize,
+    /// The selectable rows of the scrollable list in view
+    pub rows: Vec<RowView>,
+    /// Rows of the list below the drawn ones, out of view
+    pub rows_after: usize,
+    /// Shown in place of the list when nothing matches the query
+    pub placeholder: Option<String>,
+    /// A row pinned below the list which creates something from the query, or runs it
+    pub create: Option<RowView>,
+    /// Facts about the selected item by label, under the list
+    pub details: Vec<(String, String)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EntryView {
+    /// Placeholder shown while the entry is empty
+    pub hint: String,
+    pub value: String,
+    /// Drawn as dots, as the value is a secret
+    pub obscured: bool,
+    /// How many items match the query out of how many there are, such as "12/408", drawn beside
+    /// the entry.  Only the matches when the provider matches queries itself.
+    pub counter: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CountdownView {
+    /// What happens once time runs out and in how long, such as "closing in 7 s"
+    pub label: String,
+    /// The share of the timeout left, from 1 down to 0, drawn as a thin bar
+    pub left: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RowView {
+    /// The name of the section the row starts, drawn above it, unselectable
+    pub header: Option<String>,
+    pub label: String,
+    /// A secondary line under the label
+    pub subtitle: Option<String>,
+    /// A short tag at the end of the row
+    pub badge: Option<String>,
+    /// The icon file drawn at the start of the row, once it has been looked up
+    pub icon: Option<PathBuf>,
+    /// The digit which with Alt takes the row, on the first rows in view
+    pub shortcut: Option<String>,
+    /// Byte ranges of the label matching the query, drawn emphasized
+    pub highlights: Vec<Range<usize>>,
+    /// Drawn with the primary style
+    pub selected: bool,
+    /// Marked to be taken with the other marked rows
+    pub marked: bool,
+    /// Drawn grayed out, as a reload is pending
+    pub stale: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use iced_core::keyboard::key::Named;
+    use iced_core::keyboard::{Key, Modifiers};
+    use insta::assert_yaml_snapshot;
+
+    use crate::testing::{loaded, with_provider, TestProvider, APP_TITLES};
+    use crate::IliaMessage;
+
+    fn press(key: Named) -> IliaMessage<crate::testing::TestItem> {
+        IliaMessage::KeyEvent(Key::Named(key), Modifiers::empty())
+    }
+
+    #[test]
+    fn test_view_empty() {
+        assert_yaml_snapshot!(loaded::<&str>(&[]).view_tree());
+    }
+
+    #[test]
+    fn test_view_loaded() {
+        assert_yaml_snapshot!(loaded(&APP_TITLES).view_tree());
+    }
+
+    #[test]
+    fn test_view_entry_hidden() {
+        let mut unit = loaded(&APP_TITLES);
+        unit.state.entry_hidden = true;
+        let _ = unit.update(press(Named::ArrowDown));
+        assert_yaml_snapshot!(unit.view_tree());
+    }
+
+    #[test]
+    fn test_view_filtered() {
+        let mut unit = loaded(&APP_TITLES);
+        let _ = unit.update(IliaMessage::EntryUpdate(String::from("fi")));
+        assert_yaml_snapshot!(unit.view_tree());
+    }
+
+    #[test]
+    fn test_view_selected() {
+        let mut unit = loaded(&APP_TITLES);
+        let _ = unit.update(press(Named::ArrowDown));
+        let _ = unit.update(press(Named::ArrowDown));
+        assert_yaml_snapshot!(unit.view_tree());
+    }
+
+    #[test]
+    fn test_view_palette() {
+        let mut unit = loaded(&APP_TITLES);
+        let _ = unit.update(IliaMessage::EntryUpdate(String::from(">reload")));
+        assert_yaml_snapshot!(unit.view_tree());
+    }
+
+    #[test]
+    fn test_view_reloading() {
+        let mut unit = loaded(&APP_TITLES);
+        let _ = unit.update(press(Named::F5));
+        assert_yaml_snapshot!(unit.view_tree());
+    }
+
+    #[test]
+    fn test_view_error() {
+        let mut unit = loaded(&APP_TITLES);
+        let _ = unit.update(IliaMessage::ExecuteFinished(Err(String::from(
+            "Unable to focus Firefox",
+        ))));
+        assert_yaml_snapshot!(unit.view_tree());
+    }
+
+    #[test]
+    fn test_view_busy() {
+        let mut unit = loaded(&APP_TITLES);
+        let _ = unit.update(press(Named::Enter));
+        assert_yaml_snapshot!(unit.view_tree());
+    }
+
+    #[test]
+    fn test_view_no_matches() {
+        let mut unit = loaded(&APP_TITLES);
+        unit.flags.config.run_unmatched = true;
+        let _ = unit.update(IliaMessage::EntryUpdate(String::from("notify-send hi")));
+        assert_yaml_snapshot!(unit.view_tree());
+    }
+
+    #[test]
+    fn test_view_create() {
+        let mut unit = with_provider(TestProvider {
+            creates: true,
+            ..TestProvider::new(&APP_TITLES)
+        });
+        let _ = unit.update(IliaMessage::EntryUpdate(String::from("fi")));
+        // Past the last result onto the pinned row, and no further
+        for _ in 0..4 {
+            let _ = unit.update(press(Named::ArrowDown));
+        }
+        assert_yaml_snapshot!(unit.view_tree());
+    }
+}
@@ -0,0 +1,14 @@
+//! The command line of ilia-adjust, also read by `cargo xtask man`
+use clap::Parser;
+
+/// A quick adjuster of volume and brightness
+///
+/// Sets the control named by the query, such as "vol 40" for the volume of the default sink
+/// through pactl, or "bright +10" for the backlight through brightnessctl.  A leading sign changes
+/// the control by that many percent rather than setting it.
+#[derive(Debug, Parser)]
+#[command(version, after_long_help = common::CONFIG_HELP)]
+pub struct Args {
+    #[command(flatten)]
+    pub common: common::CommonArgs,
+}
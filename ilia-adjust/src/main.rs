@@ -0,0 +1,27 @@
+//! ilia-adjust, a quick adjuster of volume and brightness
+use clap::Parser;
+use common::{Config, IliaConfiguration};
+use providers::AdjustProvider;
+use std::sync::{Arc, LazyLock};
+
+static PROGRAM_NAME: LazyLock<String> = std::sync::LazyLock::new(|| String::from("ilia-adjust"));
+
+/// Program entrypoint.  Just configures the app, window, and kicks off the iced runtime.
+fn main() -> iced::Result {
+    let args = ilia_adjust::Args::parse();
+    if args.common.profile {
+        common::profile_startup();
+    }
+    let config = Config::load();
+    let entry_hint = args
+        .common
+        .prompt
+        .unwrap_or_else(|| config.prompt(&PROGRAM_NAME, common::tr("hint-adjust")));
+    common::run(
+        PROGRAM_NAME.as_str(),
+        IliaConfiguration {
+            kiosk: args.common.kiosk,
+            ..IliaConfiguration::new(Arc::new(AdjustProvider), entry_hint, config)
+        },
+    )
+}
@@ -0,0 +1,13 @@
+//! The command line of ilia-autostart, also read by `cargo xtask man`
+use clap::Parser;
+
+/// A manager for XDG autostart entries
+///
+/// Lists the autostart entries and toggles whether the selected one is started at login, by writing
+/// an override into the user autostart directory.
+#[derive(Debug, Parser)]
+#[command(version, after_long_help = common::CONFIG_HELP)]
+pub struct Args {
+    #[command(flatten)]
+    pub common: common::CommonArgs,
+}
@@ -0,0 +1,15 @@
+//! The command line of ilia-context, also read by `cargo xtask man`
+use clap::Parser;
+
+/// A switcher of kubectl and docker contexts
+///
+/// Lists the kubectl contexts, badging the active one, and switches kubectl to the selected one.
+#[derive(Debug, Parser)]
+#[command(version, after_long_help = common::CONFIG_HELP)]
+pub struct Args {
+    /// List the docker contexts too, switching docker to the selected one of them
+    #[arg(long)]
+    pub docker: bool,
+    #[command(flatten)]
+    pub common: common::CommonArgs,
+}
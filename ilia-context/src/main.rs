@@ -0,0 +1,31 @@
+//! ilia-context, a switcher of kubectl and docker contexts
+use clap::Parser;
+use common::{Config, IliaConfiguration};
+use providers::ContextProvider;
+use std::sync::{Arc, LazyLock};
+
+static PROGRAM_NAME: LazyLock<String> = std::sync::LazyLock::new(|| String::from("ilia-context"));
+
+/// Program entrypoint.  Just configures the app, window, and kicks off the iced runtime.
+fn main() -> iced::Result {
+    let args = ilia_context::Args::parse();
+    if args.common.profile {
+        common::profile_startup();
+    }
+    let config = Config::load();
+    let entry_hint = args
+        .common
+        .prompt
+        .unwrap_or_else(|| config.prompt(&PROGRAM_NAME, common::tr("hint-context")));
+    common::run(
+        PROGRAM_NAME.as_str(),
+        IliaConfiguration {
+            kiosk: args.common.kiosk,
+            ..IliaConfiguration::new(
+                Arc::new(ContextProvider::default().docker(args.docker)),
+                entry_hint,
+                config,
+            )
+        },
+    )
+}
@@ -0,0 +1,42 @@
+//! The command line of ilia-dmenu, also read by `cargo xtask man`
+use std::path::PathBuf;
+
+use clap::Parser;
+
+/// A picker for lines read from standard input, printing the selected one
+///
+/// Reads one item per line until the end of input. With --listen, items also arrive over a Unix
+/// socket while open: a message starts with replace, append or clear on its own line, followed by
+/// the items.
+#[derive(Debug, Parser)]
+#[command(version, after_long_help = common::CONFIG_HELP)]
+pub struct Args {
+    /// Keep accepting items over a Unix socket while open, at the given path or in
+    /// $XDG_RUNTIME_DIR/ilia/dmenu.sock
+    #[arg(long, value_name = "SOCKET")]
+    pub listen: Option<Option<PathBuf>>,
+    /// List lines read more than once only where they were first read
+    #[arg(short, long, conflicts_with = "listen")]
+    pub unique: bool,
+    /// With --unique, mark lines read more than once with how often they were read. The lines
+    /// are then listed once all input is read.
+    #[arg(long, requires = "unique")]
+    pub count: bool,
+    /// How the selected line is printed: {text} is the line, {index} its position in the input
+    /// from 0 and {value} the line after its first tab, or all of it.  {{ and }} print braces.
+    #[arg(long, value_name = "FORMAT")]
+    pub output_format: Option<String>,
+    /// Count the selected line in the file, listing the lines selected most often first while the
+    /// query is empty
+    #[arg(long, value_name = "FILE")]
+    pub history: Option<PathBuf>,
+    /// Close without a selection after this many seconds, unless a key is pressed before
+    #[arg(long, value_name = "SECS")]
+    pub timeout: Option<f64>,
+    /// With --timeout, print the selected line once time runs out rather than close without one,
+    /// for menus offering a default choice
+    #[arg(long, requires = "timeout")]
+    pub select_default_on_timeout: bool,
+    #[command(flatten)]
+    pub common: common::CommonArgs,
+}
@@ -0,0 +1,91 @@
+//! ilia-dmenu, a picker for lines read from standard input, printing the selected one
+use std::io::{BufRead, IsTerminal};
+use std::sync::{Arc, LazyLock};
+use std::time::Duration;
+
+use anyhow::Context;
+use clap::Parser;
+use common::{Config, EmptyOrder, IliaConfiguration, Timeout};
+use ilia_dmenu::Args;
+use providers::{Duplicates, OutputFormat, SocketProvider, StdinProvider};
+
+static PROGRAM_NAME: LazyLock<String> = std::sync::LazyLock::new(|| String::from("ilia-dmenu"));
+
+/// Program entrypoint.  Just configures the app, window, and kicks off the iced runtime.
+fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+    if args.common.profile {
+        common::profile_startup();
+    }
+    let config = Config::load();
+    let entry_hint = args
+        .common
+        .prompt
+        .unwrap_or_else(|| config.prompt(&PROGRAM_NAME, common::tr("hint-dmenu")));
+    if let Some(format) = &args.output_format {
+        OutputFormat::parse(format)?.install();
+    }
+    let timeout = match args.timeout {
+        Some(secs) => Some(Timeout {
+            after: Duration::try_from_secs_f64(secs).context("Invalid --timeout")?,
+            take_selected: args.select_default_on_timeout,
+        }),
+        None => None,
+    };
+    // Lines taken before lead the list, as dmenu's history patch orders them
+    let empty_order = if args.history.is_some() {
+        EmptyOrder::Frequency
+    } else {
+        EmptyOrder::Load
+    };
+
+    match args.listen {
+        Some(path) => {
+            let path = path
+                .or_else(SocketProvider::default_path)
+                .context("XDG_RUNTIME_DIR is not set, pass a socket path")?;
+            // Items may all arrive over the socket, with nothing piped in
+            let lines = if std::io::stdin().is_terminal() {
+                vec![]
+            } else {
+                std::io::stdin()
+                    .lock()
+                    .lines()
+                    .collect::<Result<Vec<_>, _>>()?
+            };
+            common::run(
+                PROGRAM_NAME.as_str(),
+                IliaConfiguration {
+                    kiosk: args.common.kiosk,
+                    timeout,
+                    empty_order,
+                    frecency_file: args.history,
+                    ..IliaConfiguration::new(
+                        Arc::new(SocketProvider::bind(&path, lines)?),
+                        entry_hint,
+                        config,
+                    )
+                },
+            )?
+        }
+        None => common::run(
+            PROGRAM_NAME.as_str(),
+            IliaConfiguration {
+                kiosk: args.common.kiosk,
+                timeout,
+                empty_order,
+                frecency_file: args.history,
+                ..IliaConfiguration::new(
+                    Arc::new(StdinProvider::new(match (args.unique, args.count) {
+                        (false, _) => Duplicates::Keep,
+                        (true, false) => Duplicates::Collapse,
+                        (true, true) => Duplicates::Count,
+                    })),
+                    entry_hint,
+                    config,
+                )
+            },
+        )?,
+    }
+    Ok(())
+}
@@ -0,0 +1,14 @@
+//! The command line of ilia-drun, also read by `cargo xtask man`
+use clap::Parser;
+
+/// A desktop app launcher
+///
+/// Lists the apps of the desktop entries in the XDG data directories, and starts the selected one.
+/// A leading > in the query opens the command palette, and Ctrl+Enter opens it on the selected
+/// app's own actions, such as a new private window.
+#[derive(Debug, Parser)]
+#[command(version, after_long_help = common::CONFIG_HELP)]
+pub struct Args {
+    #[command(flatten)]
+    pub common: common::CommonArgs,
+}
@@ -1,79 +1,30 @@
 //! ilia-drun, a desktop app launcher
-use common::{iced_settings, window_settings, Ilia, IliaConfiguration, ItemDescriptor};
-use std::process::exit;
-use std::sync::LazyLock;
-
-use anyhow::Context;
-use freedesktop_desktop_entry::{default_paths, DesktopEntry, Iter as DesktopIter};
-use iced::Theme;
+use clap::Parser;
+use common::{Config, EmptyOrder, IliaConfiguration};
+use providers::DesktopEntryProvider;
+use std::sync::{Arc, LazyLock};
 
 static PROGRAM_NAME: LazyLock<String> = std::sync::LazyLock::new(|| String::from("ilia-drun"));
 
-#[derive(Debug, Clone)]
-struct Item {
-    desktop_entry: DesktopEntry<'static>,
-}
-
-impl ItemDescriptor for Item {
-    fn title(&self) -> &str {
-        self.desktop_entry.desktop_entry("Name").unwrap_or("err")
-    }
-
-    fn exec(&self) -> anyhow::Result<()> {
-        let args = shell_words::split(self.desktop_entry.exec().context("Unable to get exec")?)?;
-        let args = args
-            .iter()
-            // Filter out special freedesktop syntax
-            .filter(|entry| !entry.starts_with('%'))
-            .collect::<Vec<&String>>();
-
-        std::process::Command::new(args[0])
-            .args(&args[1..])
-            .spawn()
-            .context("Failed to spawn app")
-            .map(|_| ())?;
-
-        exit(0);
-    }
-}
-
-impl From<DesktopEntry<'static>> for Item {
-    fn from(value: DesktopEntry<'static>) -> Self {
-        Item {
-            desktop_entry: value,
-        }
-    }
-}
-
 /// Program entrypoint.  Just configures the app, window, and kicks off the iced runtime.
 fn main() -> iced::Result {
-    let app_factory = || {
-        Ilia::new(IliaConfiguration {
-            item_loader: load_apps,
-            entry_hint: String::from("drun"),
-        })
-    };
-
-    iced::application(PROGRAM_NAME.as_str(), Ilia::update, Ilia::view)
-        .settings(iced_settings(PROGRAM_NAME.as_str()))
-        .window(window_settings(PROGRAM_NAME.as_str()))
-        .theme(|_| Theme::Nord)
-        .subscription(Ilia::subscription)
-        .run_with(app_factory)
-}
-
-/// Load DesktopEntry's from `DesktopIter`
-fn load_apps() -> Vec<Item> {
-    DesktopIter::new(default_paths())
-        .map(|path| DesktopEntry::from_path::<String>(path, None))
-        .filter_map(|entry_result| {
-            if let Ok(entry) = entry_result {
-                Some(Item::from(entry))
-            } else {
-                None
-            }
-        })
-        .collect()
+    let args = ilia_drun::Args::parse();
+    if args.common.profile {
+        common::profile_startup();
+    }
+    let config = Config::load();
+    let entry_hint = args
+        .common
+        .prompt
+        .unwrap_or_else(|| config.prompt(&PROGRAM_NAME, common::tr("hint-drun")));
+    common::run(
+        PROGRAM_NAME.as_str(),
+        IliaConfiguration {
+            kiosk: args.common.kiosk,
+            empty_order: EmptyOrder::Frecency,
+            ..IliaConfiguration::new(Arc::new(DesktopEntryProvider), entry_hint, config)
+        },
+    )
 }
 
 /*
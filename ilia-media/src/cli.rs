@@ -0,0 +1,13 @@
+//! The command line of ilia-media, also read by `cargo xtask man`
+use clap::Parser;
+
+/// A controller for MPRIS media players
+///
+/// Lists the media players on the session bus and toggles playback of the selected one.  Ctrl+Enter
+/// opens the command palette on the selected player, to skip to its next or previous track.
+#[derive(Debug, Parser)]
+#[command(version, after_long_help = common::CONFIG_HELP)]
+pub struct Args {
+    #[command(flatten)]
+    pub common: common::CommonArgs,
+}
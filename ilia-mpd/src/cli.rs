@@ -0,0 +1,14 @@
+//! The command line of ilia-mpd, also read by `cargo xtask man`
+use clap::Parser;
+
+/// A search of the MPD music library
+///
+/// Searches the library of the MPD server at MPD_HOST and MPD_PORT as the query changes, and plays
+/// the selected track.  Ctrl+Enter opens the command palette on the selected track, to add it to
+/// the queue without playing it.
+#[derive(Debug, Parser)]
+#[command(version, after_long_help = common::CONFIG_HELP)]
+pub struct Args {
+    #[command(flatten)]
+    pub common: common::CommonArgs,
+}
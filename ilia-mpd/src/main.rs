@@ -0,0 +1,27 @@
+//! ilia-mpd, a search of the MPD music library which plays the selected track
+use clap::Parser;
+use common::{Config, IliaConfiguration};
+use providers::MpdProvider;
+use std::sync::{Arc, LazyLock};
+
+static PROGRAM_NAME: LazyLock<String> = std::sync::LazyLock::new(|| String::from("ilia-mpd"));
+
+/// Program entrypoint.  Just configures the app, window, and kicks off the iced runtime.
+fn main() -> iced::Result {
+    let args = ilia_mpd::Args::parse();
+    if args.common.profile {
+        common::profile_startup();
+    }
+    let config = Config::load();
+    let entry_hint = args
+        .common
+        .prompt
+        .unwrap_or_else(|| config.prompt(&PROGRAM_NAME, common::tr("hint-mpd")));
+    common::run(
+        PROGRAM_NAME.as_str(),
+        IliaConfiguration {
+            kiosk: args.common.kiosk,
+            ..IliaConfiguration::new(Arc::new(MpdProvider), entry_hint, config)
+        },
+    )
+}
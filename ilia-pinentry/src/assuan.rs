@@ -0,0 +1,248 @@
+//! The server side of the pinentry protocol, an Assuan dialect spoken by gpg-agent over stdin
+//! and stdout.  Each prompt is shown by a child process, as the UI can only run once per process.
+use std::io::{BufRead, Write};
+use std::process::{Command, Stdio};
+
+use anyhow::Context;
+
+use crate::{EXIT_NOT_CONFIRMED, PROGRAM_NAME};
+
+/// GPG_ERR_CANCELED from the pinentry error source
+const ERR_CANCELED: &str = "ERR 83886179 Operation cancelled <Pinentry>";
+/// GPG_ERR_NOT_CONFIRMED from the pinentry error source
+const ERR_NOT_CONFIRMED: &str = "ERR 83886194 Not confirmed <Pinentry>";
+/// GPG_ERR_ASS_UNKNOWN_CMD from the pinentry error source
+const ERR_UNKNOWN_COMMAND: &str = "ERR 83886355 Unknown IPC command <Pinentry>";
+
+/// The texts of the next prompt, as set by the client
+#[derive(Debug, Default)]
+struct Prompt {
+    description: Option<String>,
+    prompt: Option<String>,
+    error: Option<String>,
+    ok: Option<String>,
+    cancel: Option<String>,
+}
+
+impl Prompt {
+    // Arguments for the child process showing this prompt
+    fn args(&self, mode: &str) -> Vec<String> {
+        let mut args = vec![String::from(mode)];
+        let message = [self.error.as_deref(), self.description.as_deref()]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        if !message.is_empty() {
+            args.extend([String::from("--description"), message]);
+        }
+        for (flag, value) in [
+            ("--prompt", &self.prompt),
+            ("--ok", &self.ok),
+            ("--cancel", &self.cancel),
+        ] {
+            if let Some(value) = value {
+                args.extend([String::from(flag), value.clone()]);
+            }
+        }
+        args
+    }
+}
+
+/// Answer the client's commands until it says goodbye or hangs up
+pub fn serve(input: impl BufRead, mut output: impl Write) -> anyhow::Result<()> {
+    writeln!(output, "OK Pleased to meet you")?;
+    output.flush()?;
+
+    let mut prompt = Prompt::default();
+    for line in input.lines() {
+        let line = line.context("Failed to read command")?;
+        let (command, argument) = line.split_once(' ').unwrap_or((line.as_str(), ""));
+        let argument = unescape(argument);
+
+        let reply = match command.to_ascii_uppercase().as_str() {
+            "SETDESC" => set(&mut prompt.description, argument),
+            "SETPROMPT" => set(&mut prompt.prompt, argument),
+            "SETERROR" => set(&mut prompt.error, argument),
+            "SETOK" => set(&mut prompt.ok, argument),
+            "SETCANCEL" => set(&mut prompt.cancel, argument),
+            "GETPIN" => {
+                let reply = match show(&prompt.args("--getpin"))? {
+                    Outcome::Accepted(pin) => format!("D {}\nOK", escape(&pin)),
+                    Outcome::Cancelled | Outcome::Refused => String::from(ERR_CANCELED),
+                };
+                // An error is only shown for the attempt it was set for
+                prompt.error = None;
+                reply
+            }
+            "CONFIRM" | "MESSAGE" => {
+                let mode = if command.eq_ignore_ascii_case("MESSAGE") {
+                    "--message"
+                } else {
+                    "--confirm"
+                };
+                let reply = match show(&prompt.args(mode))? {
+                    Outcome::Accepted(_) => String::from("OK"),
+                    Outcome::Refused => String::from(ERR_NOT_CONFIRMED),
+                    Outcome::Cancelled => String::from(ERR_CANCELED),
+                };
+                prompt.error = None;
+                reply
+            }
+            "GETINFO" => match argument.as_str() {
+                "version" => format!("D {}\nOK", env!("CARGO_PKG_VERSION")),
+                "pid" => format!("D {}\nOK", std::process::id()),
+                "flavor" => String::from("D ilia\nOK"),
+                "ttyinfo" => String::from("D - - -\nOK"),
+                _ => String::from(ERR_UNKNOWN_COMMAND),
+            },
+            "RESET" => {
+                prompt = Prompt::default();
+                String::from("OK")
+            }
+            // Options, key info and the other settings have no bearing on how ilia prompts
+            "OPTION" | "SETTITLE" | "SETKEYINFO" | "SETQUALITYBAR" | "SETQUALITYBAR_TT"
+            | "SETGENPIN" | "SETGENPIN_TT" | "SETREPEAT" | "SETREPEATERROR" | "SETNOTOK"
+            | "SETTIMEOUT" | "CLEARPASSPHRASE" | "NOP" => String::from("OK"),
+            "BYE" => {
+                writeln!(output, "OK closing connection")?;
+                return Ok(output.flush()?);
+            }
+            _ => String::from(ERR_UNKNOWN_COMMAND),
+        };
+        writeln!(output, "{}", reply)?;
+        output.flush()?;
+    }
+    Ok(())
+}
+
+fn set(field: &mut Option<String>, value: String) -> String {
+    *field = Some(value).filter(|value| !value.is_empty());
+    String::from("OK")
+}
+
+/// How the user answered a prompt
+enum Outcome {
+    /// Entered a PIN or chose OK
+    Accepted(String),
+    /// Chose the cancel button of a confirmation
+    Refused,
+    /// Dismissed the prompt
+    Cancelled,
+}
+
+// Show a prompt in a child process, which prints the PIN and exits 0 when accepted
+fn show(args: &[String]) -> anyhow::Result<Outcome> {
+    let exe = std::env::current_exe().context("Unable to find own executable")?;
+    let output = Command::new(exe)
+        .args(args)
+        .stdin(Stdio::null())
+        .stderr(Stdio::inherit())
+        .output()
+        .with_context(|| format!("Failed to run {}", PROGRAM_NAME.as_str()))?;
+
+    Ok(match output.status.code() {
+        Some(0) => {
+            let pin = String::from_utf8(output.stdout).context("PIN is not UTF-8")?;
+            Outcome::Accepted(pin.strip_suffix('\n').unwrap_or(&pin).to_string())
+        }
+        Some(EXIT_NOT_CONFIRMED) => Outcome::Refused,
+        _ => Outcome::Cancelled,
+    })
+}
+
+/// Percent-escape a data line, as the protocol requires of '%', CR and LF
+fn escape(data: &str) -> String {
+    data.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Decode the percent-escapes of a command argument
+fn unescape(argument: &str) -> String {
+    let bytes = argument.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let escaped = bytes
+            .get(index + 1..index + 3)
+            .filter(|_| bytes[index] == b'%')
+            .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                index += 3;
+            }
+            None => {
+                decoded.push(bytes[index]);
+                index += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unescape() {
+        assert_eq!(
+            unescape("Please enter the passphrase%0Afor key %22work%22"),
+            "Please enter the passphrase\nfor key \"work\""
+        );
+        assert_eq!(unescape("100%25 sure%0D%0A"), "100% sure\r\n");
+        // Escapes may hold any byte of UTF-8, in either case
+        assert_eq!(unescape("Caf%C3%a9"), "Café");
+        // Percent signs not followed by two hex digits are kept as they are
+        assert_eq!(unescape("50% off%"), "50% off%");
+        assert_eq!(unescape("%zz%4"), "%zz%4");
+        // An escape never decodes twice
+        assert_eq!(unescape("%2541"), "%41");
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("plain pin"), "plain pin");
+        assert_eq!(escape("50%\r\nmore"), "50%25%0D%0Amore");
+        // What the client decodes is the PIN as entered
+        for pin in ["%0A", "line\nbreak", "%%25\r"] {
+            assert_eq!(unescape(&escape(pin)), pin);
+        }
+    }
+
+    #[test]
+    fn test_prompt_args() {
+        let mut prompt = Prompt::default();
+        set(&mut prompt.description, unescape("Unlock%0Athe key"));
+        set(&mut prompt.prompt, unescape("PIN:"));
+        set(&mut prompt.error, unescape("Bad PIN"));
+        // An empty argument unsets the text
+        set(&mut prompt.ok, String::new());
+        assert_eq!(
+            prompt.args("--getpin"),
+            [
+                "--getpin",
+                "--description",
+                "Bad PIN\n\nUnlock\nthe key",
+                "--prompt",
+                "PIN:"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_serve() {
+        let input = "SETDESC Unlock%0Athe key\nsetprompt PIN:\nGETINFO flavor\nFROB\nBYE\nNOP\n";
+        let mut output = vec![];
+        serve(input.as_bytes(), &mut output).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            format!(
+                "OK Pleased to meet you\nOK\nOK\nD ilia\nOK\n{}\nOK closing connection\n",
+                ERR_UNKNOWN_COMMAND
+            )
+        );
+    }
+}
@@ -0,0 +1,43 @@
+//! The command line of ilia-pinentry, also read by `cargo xtask man`
+use clap::Parser;
+
+/// A pinentry for gpg-agent, prompting for passphrases with ilia
+///
+/// Speaks the pinentry protocol on standard input and output, as gpg-agent expects of its
+/// pinentry-program. Each prompt is shown in a new process running the prompt flags below.
+#[derive(Debug, Parser)]
+#[command(version, after_long_help = common::CONFIG_HELP)]
+pub struct Args {
+    /// Prompt for a PIN and print it, instead of speaking the pinentry protocol
+    #[arg(long, conflicts_with_all = ["confirm", "message"])]
+    pub getpin: bool,
+    /// Ask to confirm the description with OK or cancel
+    #[arg(long, conflicts_with = "message")]
+    pub confirm: bool,
+    /// Show the description with an OK button
+    #[arg(long)]
+    pub message: bool,
+    /// Text shown above the entry, such as what the PIN is for
+    #[arg(long)]
+    pub description: Option<String>,
+    /// Placeholder of the entry
+    #[arg(long)]
+    pub prompt: Option<String>,
+    /// Label of the OK button
+    #[arg(long)]
+    pub ok: Option<String>,
+    /// Label of the cancel button
+    #[arg(long)]
+    pub cancel: Option<String>,
+    /// Accepted for compatibility with the options gpg-agent passes to pinentries
+    #[arg(long, short = 'D', hide = true)]
+    pub display: Option<String>,
+    #[arg(long, short = 'T', hide = true)]
+    pub ttyname: Option<String>,
+    #[arg(long, short = 'N', hide = true)]
+    pub ttytype: Option<String>,
+    #[arg(long, short = 'C', hide = true)]
+    pub lc_ctype: Option<String>,
+    #[arg(long, short = 'M', hide = true)]
+    pub lc_messages: Option<String>,
+}
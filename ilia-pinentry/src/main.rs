@@ -0,0 +1,132 @@
+//! ilia-pinentry, a pinentry for gpg-agent which prompts for passphrases with ilia
+mod assuan;
+
+use std::process::exit;
+use std::sync::{Arc, LazyLock};
+
+use clap::Parser;
+use common::{Config, IliaConfiguration, ItemDescriptor, ItemProvider};
+use ilia_pinentry::Args;
+
+static PROGRAM_NAME: LazyLock<String> = std::sync::LazyLock::new(|| String::from("ilia-pinentry"));
+
+/// Exit code of a prompt whose cancel button was chosen, rather than being dismissed
+const EXIT_NOT_CONFIRMED: i32 = 2;
+
+/// The PIN as typed, printed for the protocol server when entered
+#[derive(Debug, Clone)]
+struct PinItem {
+    pin: String,
+}
+
+impl ItemDescriptor for PinItem {
+    fn title(&self) -> &str {
+        ""
+    }
+
+    fn exec(&self) -> anyhow::Result<()> {
+        print!("{}", self.pin);
+
+        exit(0);
+    }
+}
+
+/// Offers no items, only the PIN typed into the obscured entry
+#[derive(Debug)]
+struct PinProvider;
+
+impl ItemProvider for PinProvider {
+    type Item = PinItem;
+
+    fn load(&self) -> anyhow::Result<Vec<PinItem>> {
+        Ok(vec![])
+    }
+
+    fn create(&self, query: &str) -> Option<PinItem> {
+        Some(PinItem {
+            pin: query.to_string(),
+        })
+    }
+}
+
+/// A button of a confirmation, exiting with the code the protocol server expects
+#[derive(Debug, Clone)]
+struct ChoiceItem {
+    label: String,
+    code: i32,
+}
+
+impl ItemDescriptor for ChoiceItem {
+    fn title(&self) -> &str {
+        &self.label
+    }
+
+    fn exec(&self) -> anyhow::Result<()> {
+        exit(self.code);
+    }
+}
+
+#[derive(Debug)]
+struct ChoiceProvider {
+    choices: Vec<ChoiceItem>,
+}
+
+impl ItemProvider for ChoiceProvider {
+    type Item = ChoiceItem;
+
+    fn load(&self) -> anyhow::Result<Vec<ChoiceItem>> {
+        Ok(self.choices.clone())
+    }
+}
+
+/// Program entrypoint.  Speaks the pinentry protocol, unless asked to show one prompt.
+fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+
+    if !(args.getpin || args.confirm || args.message) {
+        return assuan::serve(std::io::stdin().lock(), std::io::stdout().lock());
+    }
+
+    // Mnemonics are marked with an underscore in the labels gpg-agent sends
+    let label = |label: Option<String>, default: &str| {
+        label.map_or_else(|| default.to_string(), |label| label.replace('_', ""))
+    };
+    let ok = ChoiceItem {
+        label: label(args.ok, "OK"),
+        code: 0,
+    };
+    if args.getpin {
+        common::run(
+            PROGRAM_NAME.as_str(),
+            IliaConfiguration {
+                message: args.description,
+                obscured: true,
+                ..IliaConfiguration::new(
+                    Arc::new(PinProvider),
+                    args.prompt.unwrap_or_else(|| String::from("PIN")),
+                    Config::load(),
+                )
+            },
+        )?;
+    } else {
+        let mut choices = vec![ok];
+        if args.confirm {
+            choices.push(ChoiceItem {
+                label: label(args.cancel, "Cancel"),
+                code: EXIT_NOT_CONFIRMED,
+            });
+        }
+        common::run(
+            PROGRAM_NAME.as_str(),
+            IliaConfiguration {
+                message: args.description,
+                ..IliaConfiguration::new(
+                    Arc::new(ChoiceProvider { choices }),
+                    String::new(),
+                    Config::load(),
+                )
+            },
+        )?;
+    }
+    Ok(())
+}
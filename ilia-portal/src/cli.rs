@@ -0,0 +1,41 @@
+//! The command line of ilia-portal, also read by `cargo xtask man`
+use std::path::PathBuf;
+
+use clap::{Parser, ValueEnum};
+
+/// What a chooser picks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Choice {
+    /// A file to open
+    File,
+    /// A directory
+    Directory,
+    /// Where to save a file
+    Save,
+}
+
+/// A file chooser for xdg-desktop-portal
+///
+/// Serves org.freedesktop.impl.portal.FileChooser on the session bus, showing each chooser in a new
+/// process running the --choose flags below. Install data/ilia.portal into
+/// /usr/share/xdg-desktop-portal/portals and select it in portals.conf with
+/// org.freedesktop.impl.portal.FileChooser=ilia.
+#[derive(Debug, Parser)]
+#[command(version, after_long_help = common::CONFIG_HELP)]
+pub struct Args {
+    /// Show one file chooser and print the chosen path, instead of serving the portal
+    #[arg(long)]
+    pub choose: Option<Choice>,
+    /// What the chooser is for, shown above the entry
+    #[arg(long)]
+    pub title: Option<String>,
+    /// The directory to choose below, the home directory by default
+    #[arg(long)]
+    pub folder: Option<PathBuf>,
+    /// Name of the file to save
+    #[arg(long)]
+    pub name: Option<String>,
+    /// Only offer files with this extension
+    #[arg(long = "extension")]
+    pub extensions: Vec<String>,
+}
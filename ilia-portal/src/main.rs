@@ -0,0 +1,61 @@
+//! ilia-portal, a file chooser for xdg-desktop-portal.  Install data/ilia.portal into
+//! /usr/share/xdg-desktop-portal/portals and the service file into /usr/share/dbus-1/services,
+//! then select it in portals.conf with `org.freedesktop.impl.portal.FileChooser=ilia`.
+mod portal;
+
+use std::sync::{Arc, LazyLock};
+
+use anyhow::Context;
+use clap::Parser;
+use common::{Config, IliaConfiguration};
+use ilia_portal::{Args, Choice};
+use providers::FileProvider;
+
+static PROGRAM_NAME: LazyLock<String> = std::sync::LazyLock::new(|| String::from("ilia-portal"));
+
+const BUS_NAME: &str = "org.freedesktop.impl.portal.desktop.ilia";
+const OBJECT_PATH: &str = "/org/freedesktop/portal/desktop";
+
+/// Program entrypoint.  Serves the portal on the session bus, unless asked to show one chooser.
+fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+
+    let Some(choice) = args.choose else {
+        let _connection = zbus::blocking::connection::Builder::session()?
+            .name(BUS_NAME)?
+            .serve_at(OBJECT_PATH, portal::FileChooser)?
+            .build()
+            .context("Unable to serve the portal")?;
+        loop {
+            std::thread::park();
+        }
+    };
+
+    let root = args
+        .folder
+        .or_else(dirs::home_dir)
+        .context("No folder to choose from")?;
+    let save_name =
+        (choice == Choice::Save).then(|| args.name.unwrap_or_else(|| String::from("untitled")));
+    common::run(
+        PROGRAM_NAME.as_str(),
+        IliaConfiguration {
+            message: args.title,
+            ..IliaConfiguration::new(
+                Arc::new(
+                    FileProvider::new(root)
+                        .directories(choice == Choice::Directory)
+                        .extensions(args.extensions)
+                        .save_name(save_name),
+                ),
+                common::tr(if choice == Choice::Save {
+                    "hint-save"
+                } else {
+                    "hint-open"
+                }),
+                Config::load(),
+            )
+        },
+    )?;
+    Ok(())
+}
@@ -0,0 +1,234 @@
+//! The org.freedesktop.impl.portal.FileChooser backend, which xdg-desktop-portal calls on behalf
+//! of sandboxed apps.  Each dialog is shown by a child process, as the UI can only run once per
+//! process.
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::os::unix::ffi::{OsStrExt, OsStringExt};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use anyhow::Context;
+use zbus::zvariant::{ObjectPath, OwnedValue, Value};
+
+/// Responses of the portal request
+const RESPONSE_SUCCESS: u32 = 0;
+const RESPONSE_CANCELLED: u32 = 1;
+const RESPONSE_FAILED: u32 = 2;
+
+/// A filter of the portal: a name and (kind, pattern) pairs, kind 0 being a glob
+type Filter = (String, Vec<(u32, String)>);
+
+#[derive(Debug, Default)]
+pub struct FileChooser;
+
+#[zbus::interface(name = "org.freedesktop.impl.portal.FileChooser")]
+impl FileChooser {
+    #[zbus(out_args("response", "results"))]
+    fn open_file(
+        &self,
+        _handle: ObjectPath<'_>,
+        _app_id: &str,
+        _parent_window: &str,
+        title: &str,
+        options: HashMap<&str, Value<'_>>,
+    ) -> (u32, HashMap<String, OwnedValue>) {
+        let mode = if flag(&options, "directory") {
+            "directory"
+        } else {
+            "file"
+        };
+        let mut args = vec![String::from("--choose"), String::from(mode)];
+        args.extend(common_args(title, path(&options, "current_folder")));
+        for extension in extensions(&options) {
+            args.extend([String::from("--extension"), extension]);
+        }
+        respond(choose(&args).map(|path| path.map(|path| vec![path])))
+    }
+
+    #[zbus(out_args("response", "results"))]
+    fn save_file(
+        &self,
+        _handle: ObjectPath<'_>,
+        _app_id: &str,
+        _parent_window: &str,
+        title: &str,
+        options: HashMap<&str, Value<'_>>,
+    ) -> (u32, HashMap<String, OwnedValue>) {
+        let current_file = path(&options, "current_file");
+        let folder = path(&options, "current_folder").or_else(|| {
+            current_file
+                .as_deref()
+                .and_then(Path::parent)
+                .map(Path::to_path_buf)
+        });
+        let name = string(&options, "current_name").or_else(|| {
+            current_file
+                .as_deref()
+                .and_then(Path::file_name)
+                .map(|name| name.to_string_lossy().into_owned())
+        });
+
+        let mut args = vec![String::from("--choose"), String::from("save")];
+        args.extend(common_args(title, folder));
+        if let Some(name) = name {
+            args.extend([String::from("--name"), name]);
+        }
+        respond(choose(&args).map(|path| path.map(|path| vec![path])))
+    }
+
+    /// Saving several files picks the directory they go into
+    #[zbus(out_args("response", "results"))]
+    fn save_files(
+        &self,
+        _handle: ObjectPath<'_>,
+        _app_id: &str,
+        _parent_window: &str,
+        title: &str,
+        options: HashMap<&str, Value<'_>>,
+    ) -> (u32, HashMap<String, OwnedValue>) {
+        let names: Vec<PathBuf> = options
+            .get("files")
+            .and_then(|files| Vec::<Vec<u8>>::try_from(files.try_clone().ok()?).ok())
+            .unwrap_or_default()
+            .into_iter()
+            .map(bytes_path)
+            .collect();
+
+        let mut args = vec![String::from("--choose"), String::from("directory")];
+        args.extend(common_args(title, path(&options, "current_folder")));
+        respond(
+            choose(&args)
+                .map(|dir| dir.map(|dir| names.iter().map(|name| dir.join(name)).collect())),
+        )
+    }
+
+    #[zbus(property)]
+    fn version(&self) -> u32 {
+        4
+    }
+}
+
+fn common_args(title: &str, folder: Option<PathBuf>) -> Vec<String> {
+    let mut args = vec![];
+    if !title.is_empty() {
+        args.extend([String::from("--title"), title.to_string()]);
+    }
+    if let Some(folder) = folder {
+        args.extend([String::from("--folder"), folder.display().to_string()]);
+    }
+    args
+}
+
+// Show a dialog in a child process, which prints the chosen path and exits 0
+fn choose(args: &[String]) -> anyhow::Result<Option<PathBuf>> {
+    let exe = std::env::current_exe().context("Unable to find own executable")?;
+    let output = Command::new(exe)
+        .args(args)
+        .stdin(Stdio::null())
+        .stderr(Stdio::inherit())
+        .output()
+        .context("Failed to show the file chooser")?;
+
+    match output.status.code() {
+        Some(0) => {
+            let path = output.stdout.strip_suffix(b"\n").unwrap_or(&output.stdout);
+            Ok(Some(bytes_path(path.to_vec())))
+        }
+        Some(common::EXIT_CANCELLED) => Ok(None),
+        _ => anyhow::bail!("File chooser failed with {}", output.status),
+    }
+}
+
+// The reply to the portal, with the chosen files as URIs
+fn respond(result: anyhow::Result<Option<Vec<PathBuf>>>) -> (u32, HashMap<String, OwnedValue>) {
+    let paths = match result {
+        Ok(Some(paths)) => paths,
+        Ok(None) => return (RESPONSE_CANCELLED, HashMap::new()),
+        Err(err) => {
+            eprintln!("{:#}", err);
+            return (RESPONSE_FAILED, HashMap::new());
+        }
+    };
+    let uris: Vec<String> = paths.iter().map(|path| file_uri(path)).collect();
+    match OwnedValue::try_from(Value::from(uris)) {
+        Ok(uris) => (
+            RESPONSE_SUCCESS,
+            HashMap::from([(String::from("uris"), uris)]),
+        ),
+        Err(err) => {
+            eprintln!("Unable to reply: {:#}", err);
+            (RESPONSE_FAILED, HashMap::new())
+        }
+    }
+}
+
+fn flag(options: &HashMap<&str, Value<'_>>, key: &str) -> bool {
+    options
+        .get(key)
+        .and_then(|value| bool::try_from(value).ok())
+        .unwrap_or(false)
+}
+
+fn string(options: &HashMap<&str, Value<'_>>, key: &str) -> Option<String> {
+    options
+        .get(key)
+        .and_then(|value| <&str>::try_from(value).ok())
+        .filter(|value| !value.is_empty())
+        .map(String::from)
+}
+
+// Paths are sent as NUL terminated byte arrays
+fn path(options: &HashMap<&str, Value<'_>>, key: &str) -> Option<PathBuf> {
+    options
+        .get(key)
+        .and_then(|value| Vec::<u8>::try_from(value.try_clone().ok()?).ok())
+        .map(bytes_path)
+        .filter(|path| !path.as_os_str().is_empty())
+}
+
+fn bytes_path(mut bytes: Vec<u8>) -> PathBuf {
+    if bytes.last() == Some(&0) {
+        bytes.pop();
+    }
+    PathBuf::from(OsString::from_vec(bytes))
+}
+
+// Extensions of the current filter, or else the first, from its "*.ext" globs.  Other patterns
+// and MIME types can't be told apart by extension, so a filter with any shows all files.
+fn extensions(options: &HashMap<&str, Value<'_>>) -> Vec<String> {
+    let filter = |key: &str| -> Option<Filter> {
+        Filter::try_from(options.get(key)?.try_clone().ok()?).ok()
+    };
+    let first = || {
+        options
+            .get("filters")
+            .and_then(|filters| Vec::<Filter>::try_from(filters.try_clone().ok()?).ok())
+            .and_then(|filters| filters.into_iter().next())
+    };
+    let Some((_, patterns)) = filter("current_filter").or_else(first) else {
+        return vec![];
+    };
+    patterns
+        .iter()
+        .map(|(kind, pattern)| {
+            pattern
+                .strip_prefix("*.")
+                .filter(|extension| *kind == 0 && !extension.contains(['*', '?', '[']))
+                .map(String::from)
+        })
+        .collect::<Option<Vec<_>>>()
+        .unwrap_or_default()
+}
+
+/// A file:// URI of the path, percent-encoding all but unreserved characters and slashes
+fn file_uri(path: &Path) -> String {
+    let mut uri = String::from("file://");
+    for &byte in path.as_os_str().as_bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~/".contains(&byte) {
+            uri.push(byte as char);
+        } else {
+            uri.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    uri
+}
@@ -0,0 +1,14 @@
+//! The command line of ilia-proc, also read by `cargo xtask man`
+use clap::Parser;
+
+/// A picker of running processes
+///
+/// Lists the running processes with their CPU and memory use, and ends the selected one with
+/// SIGTERM once confirmed.  Ctrl+Enter opens the command palette on the selected process, to kill
+/// it with SIGKILL instead.
+#[derive(Debug, Parser)]
+#[command(version, after_long_help = common::CONFIG_HELP)]
+pub struct Args {
+    #[command(flatten)]
+    pub common: common::CommonArgs,
+}
@@ -0,0 +1,19 @@
+//! The command line of ilia-repos, also read by `cargo xtask man`
+use std::path::PathBuf;
+
+use clap::Parser;
+
+/// A project opener of git repositories
+///
+/// Lists the git repositories below the given directories, the home directory by default, and
+/// opens the selected one in the file manager.  Ctrl+Enter opens the command palette on the
+/// selected repository, to open it in lazygit in $TERMINAL, copy its path, or open its remote in a
+/// browser.
+#[derive(Debug, Parser)]
+#[command(version, after_long_help = common::CONFIG_HELP)]
+pub struct Args {
+    /// Directories to find repositories below
+    pub roots: Vec<PathBuf>,
+    #[command(flatten)]
+    pub common: common::CommonArgs,
+}
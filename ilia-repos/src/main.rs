@@ -0,0 +1,34 @@
+//! ilia-repos, a project opener of git repositories
+use anyhow::Context;
+use clap::Parser;
+use common::{Config, IliaConfiguration};
+use providers::RepoProvider;
+use std::sync::{Arc, LazyLock};
+
+static PROGRAM_NAME: LazyLock<String> = std::sync::LazyLock::new(|| String::from("ilia-repos"));
+
+/// Program entrypoint.  Just configures the app, window, and kicks off the iced runtime.
+fn main() -> anyhow::Result<()> {
+    let args = ilia_repos::Args::parse();
+    if args.common.profile {
+        common::profile_startup();
+    }
+    let roots = if args.roots.is_empty() {
+        vec![dirs::home_dir().context("No directory to find repositories below")?]
+    } else {
+        args.roots
+    };
+    let config = Config::load();
+    let entry_hint = args
+        .common
+        .prompt
+        .unwrap_or_else(|| config.prompt(&PROGRAM_NAME, common::tr("hint-repos")));
+    common::run(
+        PROGRAM_NAME.as_str(),
+        IliaConfiguration {
+            kiosk: args.common.kiosk,
+            ..IliaConfiguration::new(Arc::new(RepoProvider::new(roots)), entry_hint, config)
+        },
+    )?;
+    Ok(())
+}
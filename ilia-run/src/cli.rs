@@ -0,0 +1,14 @@
+//! The command line of ilia-run, also read by `cargo xtask man`
+use clap::Parser;
+
+/// A runner of the executables on PATH
+///
+/// Lists the executables in the directories of PATH by name, and runs the selected one.  A query
+/// naming no listed executable is offered as a command line to run, for commands which need
+/// arguments or are not on PATH.
+#[derive(Debug, Parser)]
+#[command(version, after_long_help = common::CONFIG_HELP)]
+pub struct Args {
+    #[command(flatten)]
+    pub common: common::CommonArgs,
+}
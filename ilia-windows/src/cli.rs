@@ -0,0 +1,28 @@
+//! The command line of ilia-windows, also read by `cargo xtask man`
+use clap::Parser;
+
+/// A window switcher
+///
+/// Lists the open windows from sway or i3, or from Wayland foreign toplevel management on other
+/// wlroots compositors, and focuses the selected one.  Ctrl+Enter opens the command palette on the
+/// selected window, to close it or move it to the scratchpad.
+#[derive(Debug, Parser)]
+#[command(version, after_long_help = common::CONFIG_HELP)]
+pub struct Args {
+    /// Switch like alt-tab, when launched from a binding with a modifier such as Alt: while it is
+    /// held, Tab selects the next window and Shift+Tab the previous one, and releasing it focuses
+    /// the selected window
+    #[arg(long)]
+    pub alt_tab: bool,
+    /// Close the windows marked with Ctrl+Space when Enter is pressed, rather than move them to
+    /// the focused workspace.  Only under sway or i3.
+    #[arg(long)]
+    pub kill_marked: bool,
+    /// Take the selected window with one of its actions rather than focus it, to bind a key to
+    /// it: kill closes the window and scratchpad moves it to the scratchpad.  Only under sway or
+    /// i3, other compositors offer no actions.
+    #[arg(long, value_name = "ACTION", value_parser = ["kill", "scratchpad"])]
+    pub action: Option<String>,
+    #[command(flatten)]
+    pub common: common::CommonArgs,
+}
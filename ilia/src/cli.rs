@@ -0,0 +1,13 @@
+//! The command line of ilia, also read by `cargo xtask man`
+use clap::Parser;
+
+/// Helpers for setting up the ilia front-ends
+///
+/// Finds the ilia-* front-ends installed on PATH.
+#[derive(Debug, Parser)]
+#[command(version)]
+pub struct Args {
+    /// Print sway/i3 bindsym lines for the installed ilia modes, to include in the WM config
+    #[arg(long)]
+    pub print_sway_config: bool,
+}
@@ -0,0 +1,73 @@
+//! ilia, helpers for setting up the ilia front-ends
+use std::os::unix::fs::PermissionsExt;
+use std::path::PathBuf;
+
+use anyhow::Context;
+use clap::Parser;
+use ilia::Args;
+
+/// Prefix of the names of every front-end binary
+const MODE_PREFIX: &str = "ilia-";
+
+/// Suggested key bindings of the known front-ends, by binary name
+const SUGGESTED_KEYS: [(&str, &str); 5] = [
+    ("ilia-drun", "$mod+d"),
+    ("ilia-windows", "$mod+Tab"),
+    ("ilia-autostart", "$mod+Shift+a"),
+    ("ilia-media", "$mod+m"),
+    ("ilia-mpd", "$mod+Shift+m"),
+];
+
+fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+
+    if args.print_sway_config {
+        print!("{}", sway_config(&installed_modes()?));
+    } else {
+        eprintln!("Nothing to do, see --help");
+    }
+    Ok(())
+}
+
+/// The front-end binaries found on `PATH`, by name, first occurrence winning as in a shell
+fn installed_modes() -> anyhow::Result<Vec<(String, PathBuf)>> {
+    let path = std::env::var_os("PATH").context("PATH is not set")?;
+    let mut modes: Vec<(String, PathBuf)> = vec![];
+
+    for dir in std::env::split_paths(&path) {
+        // Skip directories which could not be read
+        let Ok(read_dir) = std::fs::read_dir(dir) else {
+            continue;
+        };
+        for entry in read_dir.filter_map(|entry| entry.ok()) {
+            let Some(name) = entry.file_name().to_str().map(String::from) else {
+                continue;
+            };
+            let is_executable = entry.path().metadata().is_ok_and(|metadata| {
+                metadata.is_file() && metadata.permissions().mode() & 0o111 != 0
+            });
+            if is_executable
+                && name.starts_with(MODE_PREFIX)
+                && !modes.iter().any(|(known, _)| *known == name)
+            {
+                modes.push((name, entry.path()));
+            }
+        }
+    }
+
+    modes.sort();
+    Ok(modes)
+}
+
+/// A block of bindsym lines, commented out for modes without a suggested key
+fn sway_config(modes: &[(String, PathBuf)]) -> String {
+    let mut config = String::from("# ilia key bindings, generated by `ilia --print-sway-config`\n");
+    for (name, path) in modes {
+        let exec = format!("exec {}", path.display());
+        match SUGGESTED_KEYS.iter().find(|(mode, _)| mode == name) {
+            Some((_, key)) => config.push_str(&format!("bindsym {} {}\n", key, exec)),
+            None => config.push_str(&format!("# bindsym $mod+<key> {}\n", exec)),
+        }
+    }
+    config
+}
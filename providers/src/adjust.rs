@@ -0,0 +1,228 @@
+use std::borrow::Cow;
+use std::process::exit;
+use std::sync::Arc;
+
+use anyhow::Context;
+use common::{ItemDescriptor, ItemProvider};
+
+use crate::tool::run;
+
+/// What a query such as "vol 40" or "bright +10" adjusts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Control {
+    /// The default PulseAudio or PipeWire sink, through pactl
+    Volume,
+    /// The backlight, through brightnessctl
+    Brightness,
+}
+
+impl Control {
+    // The control named by a prefix of at least three letters of its name, such as "vol"
+    fn parse(word: &str) -> Option<Control> {
+        let word = word.to_lowercase();
+        [
+            ("volume", Control::Volume),
+            ("brightness", Control::Brightness),
+        ]
+        .into_iter()
+        .find(|(name, _)| word.len() >= 3 && name.starts_with(&word))
+        .map(|(_, control)| control)
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Control::Volume => "volume",
+            Control::Brightness => "brightness",
+        }
+    }
+
+    // The percentage the control is at now
+    fn current(self) -> anyhow::Result<u32> {
+        let output = match self {
+            Control::Volume => run("pactl", &["get-sink-volume", "@DEFAULT_SINK@"])?,
+            Control::Brightness => run("brightnessctl", &["--machine-readable", "info"])?,
+        };
+        // pactl prints "Volume: front-left: 26214 /  40% / ...", and brightnessctl
+        // "name,class,400,40%,1000"
+        output
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .find_map(|word| word.strip_suffix('%')?.parse().ok())
+            .with_context(|| format!("No {} in the output of its tool", self.name()))
+    }
+
+    fn apply(self, change: Change) -> anyhow::Result<()> {
+        let value = match (self, change) {
+            (_, Change::To(percent)) => format!("{}%", percent),
+            (Control::Volume, Change::By(delta)) => format!("{:+}%", delta),
+            // brightnessctl takes a trailing sign for a step down
+            (Control::Brightness, Change::By(delta)) if delta < 0 => format!("{}%-", -delta),
+            (Control::Brightness, Change::By(delta)) => format!("+{}%", delta),
+        };
+        match self {
+            Control::Volume => run("pactl", &["set-sink-volume", "@DEFAULT_SINK@", &value]),
+            Control::Brightness => run("brightnessctl", &["set", &value]),
+        }
+        .map(|_| ())
+    }
+}
+
+/// How a query changes its control
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Change {
+    /// To a percentage
+    To(u32),
+    /// By percentage points up or down
+    By(i32),
+}
+
+impl Change {
+    // A change such as "40", "+10" or "-5%", of at most 100 percent
+    fn parse(word: &str) -> Option<Change> {
+        let number = word.strip_suffix('%').unwrap_or(word);
+        let change = if number.starts_with(['+', '-']) {
+            Change::By(number.parse().ok()?)
+        } else {
+            Change::To(number.parse().ok()?)
+        };
+        match change {
+            Change::To(percent) if percent > 100 => None,
+            Change::By(delta) if delta.unsigned_abs() > 100 => None,
+            change => Some(change),
+        }
+    }
+}
+
+/// A change of the volume or brightness, named after what it does
+#[derive(Debug, Clone)]
+pub struct AdjustItem {
+    control: Control,
+    change: Change,
+    title: Arc<str>,
+    /// The value the control is at now, if it could be read
+    subtitle: Option<Arc<str>>,
+}
+
+impl ItemDescriptor for AdjustItem {
+    fn title(&self) -> &str {
+        &self.title
+    }
+
+    fn id(&self) -> Cow<'_, str> {
+        Cow::Borrowed(self.control.name())
+    }
+
+    fn subtitle(&self) -> Option<&str> {
+        self.subtitle.as_deref()
+    }
+
+    fn exec(&self) -> anyhow::Result<()> {
+        self.control.apply(self.change)?;
+
+        exit(0);
+    }
+}
+
+/// Provides the change of volume or brightness a query such as "vol 40" or "bright +10" asks for
+#[derive(Debug, Default, Clone)]
+pub struct AdjustProvider;
+
+impl ItemProvider for AdjustProvider {
+    type Item = AdjustItem;
+
+    fn load(&self) -> anyhow::Result<Vec<AdjustItem>> {
+        Ok(vec![])
+    }
+
+    fn is_queryable(&self) -> bool {
+        true
+    }
+
+    fn query(&self, query: &str) -> anyhow::Result<Vec<AdjustItem>> {
+        let mut words = query.split_whitespace();
+        let (Some(control), Some(change), None) = (
+            words.next().and_then(Control::parse),
+            words.next().and_then(Change::parse),
+            words.next(),
+        ) else {
+            return Ok(vec![]);
+        };
+        let title = match change {
+            Change::To(percent) => format!("Set {} to {}%", control.name(), percent),
+            Change::By(delta) if delta < 0 => format!("Lower {} by {}%", control.name(), -delta),
+            Change::By(delta) => format!("Raise {} by {}%", control.name(), delta),
+        };
+        // The tool may be missing, which only shows when the change is applied
+        let subtitle = control
+            .current()
+            .ok()
+            .map(|percent| Arc::from(format!("Now {}%", percent)));
+        Ok(vec![AdjustItem {
+            control,
+            change,
+            title: Arc::from(title),
+            subtitle,
+        }])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_control() {
+        assert_eq!(Control::parse("vol"), Some(Control::Volume));
+        assert_eq!(Control::parse("Volume"), Some(Control::Volume));
+        assert_eq!(Control::parse("bright"), Some(Control::Brightness));
+        // Too short to tell, or not a control
+        assert_eq!(Control::parse("vo"), None);
+        assert_eq!(Control::parse("volumes"), None);
+        assert_eq!(Control::parse("mic"), None);
+    }
+
+    #[test]
+    fn test_parse_change() {
+        assert_eq!(Change::parse("40"), Some(Change::To(40)));
+        assert_eq!(Change::parse("40%"), Some(Change::To(40)));
+        assert_eq!(Change::parse("+10"), Some(Change::By(10)));
+        assert_eq!(Change::parse("-5%"), Some(Change::By(-5)));
+        assert_eq!(Change::parse("0"), Some(Change::To(0)));
+        assert_eq!(Change::parse("100"), Some(Change::To(100)));
+        assert_eq!(Change::parse("-100"), Some(Change::By(-100)));
+    }
+
+    #[test]
+    fn test_parse_change_out_of_range() {
+        for word in ["101", "400%", "+101", "-250", "99999999999", "-2147483648"] {
+            assert_eq!(Change::parse(word), None, "{:?}", word);
+        }
+    }
+
+    #[test]
+    fn test_query_not_a_change() {
+        for query in [
+            "",
+            "vol",
+            "40",
+            "vol loud",
+            "vol 400",
+            "vol 40 now",
+            "mic 40",
+        ] {
+            assert!(
+                AdjustProvider.query(query).unwrap().is_empty(),
+                "{:?}",
+                query
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_change_not_a_number() {
+        for word in [
+            "", "%", "+", "loud", "4O", "40%%", "+-5", "--5", "4.5", "%40", "+ 5",
+        ] {
+            assert_eq!(Change::parse(word), None, "{:?}", word);
+        }
+    }
+}
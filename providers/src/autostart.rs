@@ -0,0 +1,271 @@
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::path::PathBuf;
+use std::process::exit;
+use std::sync::Arc;
+
+use anyhow::Context;
+use common::{ItemDescriptor, ItemProvider};
+use freedesktop_desktop_entry::DesktopEntry;
+
+const DESKTOP_ENTRY_GROUP: &str = "[Desktop Entry]";
+const ENABLED_BADGE: &str = "enabled";
+const DISABLED_BADGE: &str = "disabled";
+
+/// A program started at login, and whether it is enabled
+#[derive(Debug, Clone)]
+pub struct AutostartItem {
+    /// Shared, as entries are large and the model is cloned on every reload
+    desktop_entry: Arc<DesktopEntry<'static>>,
+    /// Whether the entry will be started at login
+    enabled: bool,
+    title: Arc<str>,
+}
+
+impl ItemDescriptor for AutostartItem {
+    fn title(&self) -> &str {
+        &self.title
+    }
+
+    fn id(&self) -> Cow<'_, str> {
+        Cow::Borrowed(&self.desktop_entry.appid)
+    }
+
+    fn badge(&self) -> Option<&str> {
+        Some(if self.enabled {
+            ENABLED_BADGE
+        } else {
+            DISABLED_BADGE
+        })
+    }
+
+    fn icon(&self) -> Option<&str> {
+        self.desktop_entry.icon()
+    }
+
+    fn comment(&self) -> Option<&str> {
+        self.desktop_entry.desktop_entry("Comment")
+    }
+
+    fn exec_hint(&self) -> Option<&str> {
+        self.desktop_entry.exec()
+    }
+
+    /// Toggle the entry by writing a `Hidden` override into the user autostart directory
+    fn exec(&self) -> anyhow::Result<()> {
+        let user_dir = user_autostart_dir().context("Unable to find user config dir")?;
+        let file_name = self
+            .desktop_entry
+            .path
+            .file_name()
+            .context("Entry has no file name")?;
+        let target = user_dir.join(file_name);
+
+        let contents = std::fs::read_to_string(&self.desktop_entry.path)
+            .context("Failed to read autostart entry")?;
+
+        std::fs::create_dir_all(&user_dir).context("Failed to create autostart dir")?;
+        // Never write through a link into a file owned by someone else
+        if target.is_symlink() {
+            std::fs::remove_file(&target).context("Failed to remove autostart link")?;
+        }
+        std::fs::write(&target, with_hidden(&contents, self.enabled))
+            .context("Failed to write autostart entry")?;
+
+        exit(0);
+    }
+}
+
+impl From<DesktopEntry<'static>> for AutostartItem {
+    fn from(value: DesktopEntry<'static>) -> Self {
+        let enabled = value.desktop_entry("Hidden") != Some("true")
+            && value.desktop_entry("X-GNOME-Autostart-enabled") != Some("false");
+        let title = common::intern(value.desktop_entry("Name").unwrap_or("err"));
+
+        AutostartItem {
+            desktop_entry: Arc::new(value),
+            enabled,
+            title,
+        }
+    }
+}
+
+fn user_autostart_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("autostart"))
+}
+
+/// Autostart directories, from lowest to highest precedence
+fn autostart_dirs() -> Vec<PathBuf> {
+    let config_dirs = std::env::var("XDG_CONFIG_DIRS")
+        .ok()
+        .filter(|dirs| !dirs.is_empty())
+        .unwrap_or_else(|| String::from("/etc/xdg"));
+
+    let mut dirs: Vec<PathBuf> = config_dirs
+        .split(':')
+        .rev()
+        .map(|dir| PathBuf::from(dir).join("autostart"))
+        .collect();
+    dirs.extend(user_autostart_dir());
+    dirs
+}
+
+/// Provides the XDG autostart entries of the user and the system
+#[derive(Debug, Default, Clone)]
+pub struct AutostartProvider;
+
+impl ItemProvider for AutostartProvider {
+    type Item = AutostartItem;
+
+    /// Load autostart entries, letting user entries shadow system entries of the same file name
+    fn load(&self) -> anyhow::Result<Vec<AutostartItem>> {
+        let mut entries: BTreeMap<OsString, DesktopEntry<'static>> = BTreeMap::new();
+
+        for dir in autostart_dirs() {
+            let Ok(read_dir) = std::fs::read_dir(&dir) else {
+                continue;
+            };
+            // Key on the file name within the autostart dir rather than any link target
+            for path in read_dir
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+            {
+                if path.extension().is_none_or(|ext| ext != "desktop") {
+                    continue;
+                }
+                let Some(file_name) = path.file_name().map(|name| name.to_owned()) else {
+                    continue;
+                };
+                if let Ok(entry) = DesktopEntry::from_path::<String>(path, None) {
+                    entries.insert(file_name, entry);
+                }
+            }
+        }
+
+        let mut items: Vec<AutostartItem> =
+            entries.into_values().map(AutostartItem::from).collect();
+        items.sort_by_key(|item| {
+            item.desktop_entry
+                .desktop_entry("Name")
+                .map(str::to_lowercase)
+        });
+        Ok(items)
+    }
+}
+
+/// Return the entry contents with `Hidden` set so the entry is toggled from `enabled`
+fn with_hidden(contents: &str, enabled: bool) -> String {
+    let hidden_line = format!("Hidden={}", enabled);
+    let mut in_group = false;
+    let mut written = false;
+    let mut lines: Vec<String> = vec![];
+
+    for line in contents.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') {
+            in_group = trimmed == DESKTOP_ENTRY_GROUP;
+            lines.push(line.to_string());
+            if in_group && !written {
+                lines.push(hidden_line.clone());
+                written = true;
+            }
+            continue;
+        }
+
+        let key = trimmed.split('=').next().map(str::trim_end);
+        if in_group && key == Some("Hidden") {
+            continue;
+        }
+        // Re-enabling must also clear the GNOME specific switch
+        if in_group && !enabled && key == Some("X-GNOME-Autostart-enabled") {
+            lines.push(String::from("X-GNOME-Autostart-enabled=true"));
+            continue;
+        }
+        lines.push(line.to_string());
+    }
+
+    if !written {
+        lines.insert(0, hidden_line);
+        lines.insert(0, String::from(DESKTOP_ENTRY_GROUP));
+    }
+
+    lines.join("\n") + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+
+    fn entry(contents: &'static str) -> AutostartItem {
+        let path = Path::new("/etc/xdg/autostart/redshift.desktop");
+        AutostartItem::from(DesktopEntry::from_str::<String>(path, contents, None).unwrap())
+    }
+
+    #[test]
+    fn test_state_is_badge() {
+        let enabled = entry("[Desktop Entry]\nName=Redshift\n");
+        assert_eq!(enabled.title(), "Redshift");
+        assert_eq!(enabled.badge(), Some(ENABLED_BADGE));
+
+        let hidden = entry("[Desktop Entry]\nName=Redshift\nHidden=true\n");
+        assert_eq!(hidden.title(), "Redshift");
+        assert_eq!(hidden.badge(), Some(DISABLED_BADGE));
+
+        let gnome = entry("[Desktop Entry]\nName=Redshift\nX-GNOME-Autostart-enabled=false\n");
+        assert_eq!(gnome.badge(), Some(DISABLED_BADGE));
+    }
+
+    #[test]
+    fn test_with_hidden_replaces_key() {
+        let contents = "[Desktop Entry]\nName=Redshift\nHidden=false\nExec=redshift\n";
+        assert_eq!(
+            with_hidden(contents, true),
+            "[Desktop Entry]\nHidden=true\nName=Redshift\nExec=redshift\n"
+        );
+    }
+
+    #[test]
+    fn test_with_hidden_adds_key() {
+        let contents = "# Started by the session\n[Desktop Entry]\nName=Redshift\n";
+        assert_eq!(
+            with_hidden(contents, true),
+            "# Started by the session\n[Desktop Entry]\nHidden=true\nName=Redshift\n"
+        );
+        // Without the group, one is added
+        assert_eq!(
+            with_hidden("Name=Redshift", false),
+            "[Desktop Entry]\nHidden=false\nName=Redshift\n"
+        );
+    }
+
+    #[test]
+    fn test_with_hidden_keeps_other_groups() {
+        let contents = "[Desktop Entry]\n\
+            Name=Nextcloud\n\
+            HiddenPrefix=kept\n\
+            X-GNOME-Autostart-enabled=false\n\
+            Actions=quit;\n\
+            \n\
+            [Desktop Action quit]\n\
+            Name=Quit\n\
+            Hidden=false\n\
+            X-GNOME-Autostart-enabled=false\n";
+        assert_eq!(
+            with_hidden(contents, false),
+            "[Desktop Entry]\n\
+            Hidden=false\n\
+            Name=Nextcloud\n\
+            HiddenPrefix=kept\n\
+            X-GNOME-Autostart-enabled=true\n\
+            Actions=quit;\n\
+            \n\
+            [Desktop Action quit]\n\
+            Name=Quit\n\
+            Hidden=false\n\
+            X-GNOME-Autostart-enabled=false\n"
+        );
+    }
+}
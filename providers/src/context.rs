@@ -0,0 +1,212 @@
+use std::borrow::Cow;
+use std::process::exit;
+use std::sync::{Arc, Mutex};
+
+use anyhow::bail;
+use common::{ItemDescriptor, ItemProvider};
+
+use crate::tool::run;
+
+/// The command line tool a context belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Tool {
+    Kubectl,
+    Docker,
+}
+
+impl Tool {
+    fn name(self) -> &'static str {
+        match self {
+            Tool::Kubectl => "kubectl",
+            Tool::Docker => "docker",
+        }
+    }
+
+    // The contexts of the tool by name, and whether each is the active one
+    fn contexts(self) -> anyhow::Result<Vec<(String, bool)>> {
+        match self {
+            Tool::Kubectl => {
+                // Without a current context set, kubectl fails rather than printing nothing
+                let current = run("kubectl", &["config", "current-context"]).unwrap_or_default();
+                let names = run("kubectl", &["config", "get-contexts", "--output=name"])?;
+                Ok(kubectl_contexts(&names, &current))
+            }
+            Tool::Docker => {
+                let format = "{{.Name}}\t{{.Current}}";
+                let contexts = run("docker", &["context", "ls", "--format", format])?;
+                Ok(docker_contexts(&contexts))
+            }
+        }
+    }
+
+    fn switch(self, name: &str) -> anyhow::Result<()> {
+        match self {
+            Tool::Kubectl => run("kubectl", &["config", "use-context", name]),
+            Tool::Docker => run("docker", &["context", "use", name]),
+        }
+        .map(|_| ())
+    }
+}
+
+/// A kubectl or docker context, titled by its name
+#[derive(Debug, Clone)]
+pub struct ContextItem {
+    tool: Tool,
+    name: Arc<str>,
+    /// Whether the tool uses the context now
+    active: bool,
+}
+
+impl ItemDescriptor for ContextItem {
+    fn title(&self) -> &str {
+        &self.name
+    }
+
+    fn id(&self) -> Cow<'_, str> {
+        Cow::Owned(format!("{}:{}", self.tool.name(), self.name))
+    }
+
+    fn subtitle(&self) -> Option<&str> {
+        Some(self.tool.name())
+    }
+
+    fn badge(&self) -> Option<&str> {
+        self.active.then_some("active")
+    }
+
+    fn exec(&self) -> anyhow::Result<()> {
+        self.tool.switch(&self.name)?;
+
+        exit(0);
+    }
+}
+
+/// Provides the kubectl contexts, and the docker contexts if asked to, to switch between
+#[derive(Debug, Default, Clone)]
+pub struct ContextProvider {
+    docker: bool,
+    /// Why the tools which failed in the last load did, while others listed their contexts
+    failures: Arc<Mutex<Option<String>>>,
+}
+
+impl ContextProvider {
+    /// List the docker contexts too, after the kubectl ones
+    pub fn docker(mut self, docker: bool) -> Self {
+        self.docker = docker;
+        self
+    }
+}
+
+impl ItemProvider for ContextProvider {
+    type Item = ContextItem;
+
+    fn load(&self) -> anyhow::Result<Vec<ContextItem>> {
+        let tools: &[Tool] = if self.docker {
+            &[Tool::Kubectl, Tool::Docker]
+        } else {
+            &[Tool::Kubectl]
+        };
+        // A tool which is missing or fails leaves the contexts of the others listed
+        let (items, failures) = gather(tools.iter().map(|&tool| (tool, tool.contexts())));
+        let failures = (!failures.is_empty()).then(|| failures.join("\n"));
+        match failures {
+            Some(failures) if items.is_empty() => bail!(failures),
+            failures => {
+                *self.failures.lock().unwrap_or_else(|err| err.into_inner()) = failures;
+                Ok(items)
+            }
+        }
+    }
+
+    fn load_warning(&self) -> Option<String> {
+        self.failures
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .clone()
+    }
+}
+
+// The contexts of each tool which listed them, and why the others failed
+fn gather(
+    results: impl Iterator<Item = (Tool, anyhow::Result<Vec<(String, bool)>>)>,
+) -> (Vec<ContextItem>, Vec<String>) {
+    let mut items = vec![];
+    let mut failures = vec![];
+    for (tool, result) in results {
+        match result {
+            Ok(contexts) => items.extend(contexts.into_iter().map(|(name, active)| ContextItem {
+                tool,
+                name: Arc::from(name),
+                active,
+            })),
+            Err(err) => failures.push(format!("{:#}", err)),
+        }
+    }
+    (items, failures)
+}
+
+// The contexts `kubectl config get-contexts --output=name` printed, given the current one
+fn kubectl_contexts(names: &str, current: &str) -> Vec<(String, bool)> {
+    names
+        .lines()
+        .filter(|name| !name.is_empty())
+        .map(|name| (name.to_string(), name == current.trim()))
+        .collect()
+}
+
+// The contexts `docker context ls` printed, a name and whether it is current on each line
+fn docker_contexts(contexts: &str) -> Vec<(String, bool)> {
+    contexts
+        .lines()
+        .filter_map(|line| line.split_once('\t'))
+        .map(|(name, current)| (name.to_string(), current == "true"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::anyhow;
+
+    use super::*;
+
+    #[test]
+    fn test_kubectl_contexts() {
+        assert_eq!(
+            kubectl_contexts("kind-dev\nprod\n\n", "prod\n"),
+            [
+                (String::from("kind-dev"), false),
+                (String::from("prod"), true)
+            ]
+        );
+        // Without a current context, none is active
+        assert_eq!(
+            kubectl_contexts("kind-dev\n", ""),
+            [(String::from("kind-dev"), false)]
+        );
+    }
+
+    #[test]
+    fn test_docker_contexts() {
+        assert_eq!(
+            docker_contexts("default\tfalse\ncolima\ttrue\nnot a context\n"),
+            [
+                (String::from("default"), false),
+                (String::from("colima"), true)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_gather_keeps_working_tools() {
+        let (items, failures) = gather(
+            [
+                (Tool::Kubectl, Err(anyhow!("Unable to run kubectl"))),
+                (Tool::Docker, Ok(vec![(String::from("default"), true)])),
+            ]
+            .into_iter(),
+        );
+        let listed: Vec<String> = items.iter().map(|item| item.id().into_owned()).collect();
+        assert_eq!(listed, ["docker:default"]);
+        assert_eq!(failures, ["Unable to run kubectl"]);
+    }
+}
@@ -0,0 +1,144 @@
+use std::borrow::Cow;
+use std::process::exit;
+use std::sync::Arc;
+
+use anyhow::Context;
+use common::{Action, ItemDescriptor, ItemProvider};
+use freedesktop_desktop_entry::{default_paths, DesktopEntry, Iter as DesktopIter};
+
+/// An application launchable from its freedesktop desktop entry
+#[derive(Debug, Clone)]
+pub struct DesktopItem {
+    /// Shared, as entries are large and the model is cloned on every reload
+    desktop_entry: Arc<DesktopEntry<'static>>,
+}
+
+impl ItemDescriptor for DesktopItem {
+    fn title(&self) -> &str {
+        self.desktop_entry.desktop_entry("Name").unwrap_or("err")
+    }
+
+    fn id(&self) -> Cow<'_, str> {
+        Cow::Borrowed(&self.desktop_entry.appid)
+    }
+
+    fn subtitle(&self) -> Option<&str> {
+        self.comment()
+    }
+
+    fn icon(&self) -> Option<&str> {
+        self.desktop_entry.icon()
+    }
+
+    fn keywords(&self) -> Option<&str> {
+        self.desktop_entry.desktop_entry("Keywords")
+    }
+
+    fn comment(&self) -> Option<&str> {
+        self.desktop_entry.desktop_entry("Comment")
+    }
+
+    fn exec_hint(&self) -> Option<&str> {
+        self.desktop_entry.exec()
+    }
+
+    fn details(&self) -> Vec<(&'static str, String)> {
+        [
+            ("Exec", self.desktop_entry.exec().map(String::from)),
+            (
+                "Categories",
+                self.desktop_entry
+                    .desktop_entry("Categories")
+                    .map(|categories| categories.trim_end_matches(';').replace(';', ", ")),
+            ),
+            ("File", Some(self.desktop_entry.path.display().to_string())),
+        ]
+        .into_iter()
+        .filter_map(|(label, value)| Some((label, value?)))
+        .collect()
+    }
+
+    fn exec(&self) -> anyhow::Result<()> {
+        spawn_exec(self.desktop_entry.exec().context("Unable to get exec")?)?;
+
+        exit(0);
+    }
+}
+
+/// Spawn the command line from an `Exec` key
+fn spawn_exec(exec: &str) -> anyhow::Result<()> {
+    common::check_allowed(exec)?;
+    let args = shell_words::split(exec)?;
+    let args = args
+        .iter()
+        // Filter out special freedesktop syntax
+        .filter(|entry| !entry.starts_with('%'))
+        .collect::<Vec<&String>>();
+    common::check_args(&args).with_context(|| format!("Malformed Exec {:?}", exec))?;
+
+    common::command(args[0])
+        .args(&args[1..])
+        .spawn()
+        .context("Failed to spawn app")
+        .map(|_| ())
+}
+
+impl From<DesktopEntry<'static>> for DesktopItem {
+    fn from(value: DesktopEntry<'static>) -> Self {
+        DesktopItem {
+            desktop_entry: Arc::new(value),
+        }
+    }
+}
+
+/// Provides the desktop entries found in the default XDG data paths
+#[derive(Debug, Default, Clone)]
+pub struct DesktopEntryProvider;
+
+impl ItemProvider for DesktopEntryProvider {
+    type Item = DesktopItem;
+
+    /// Load DesktopEntry's from `DesktopIter`
+    fn load(&self) -> anyhow::Result<Vec<DesktopItem>> {
+        Ok(DesktopIter::new(default_paths())
+            .map(|path| DesktopEntry::from_path::<String>(path, None))
+            .filter_map(|entry_result| {
+                if let Ok(entry) = entry_result {
+                    Some(DesktopItem::from(entry))
+                } else {
+                    None
+                }
+            })
+            .collect())
+    }
+
+    /// Desktop actions declared by the entry, such as "New Private Window"
+    fn actions(&self, item: &DesktopItem) -> Vec<Action<DesktopItem>> {
+        item.desktop_entry
+            .actions()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|action| !action.is_empty())
+            .map(|action| {
+                let action = action.to_string();
+                Action {
+                    id: "desktop-action",
+                    name: item
+                        .desktop_entry
+                        .action_name::<&str>(&action, &[])
+                        .map(|name| name.to_string())
+                        .unwrap_or_else(|| action.clone()),
+                    run: Arc::new(move |item: &DesktopItem| {
+                        spawn_exec(
+                            item.desktop_entry
+                                .action_exec(&action)
+                                .context("Unable to get action exec")?,
+                        )?;
+
+                        exit(0);
+                    }),
+                }
+            })
+            .collect()
+    }
+}
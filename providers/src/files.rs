@@ -0,0 +1,158 @@
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
+use std::process::exit;
+use std::sync::Arc;
+
+use anyhow::Context;
+use common::{ItemDescriptor, ItemProvider};
+
+/// Levels of directories below the root which are listed
+const MAX_DEPTH: usize = 5;
+/// Entries listed at most, so picking from the home directory stays quick
+const MAX_ITEMS: usize = 20_000;
+
+/// A file or directory below the provider's root, or a file to save
+#[derive(Debug, Clone)]
+pub struct FileItem {
+    path: PathBuf,
+    /// The path relative to the root, directories ending in a slash
+    title: Arc<str>,
+}
+
+impl ItemDescriptor for FileItem {
+    fn title(&self) -> &str {
+        &self.title
+    }
+
+    fn id(&self) -> Cow<'_, str> {
+        self.path.to_string_lossy()
+    }
+
+    /// Print the chosen path for the program which asked for it
+    fn exec(&self) -> anyhow::Result<()> {
+        println!("{}", self.path.display());
+
+        exit(0);
+    }
+}
+
+/// Provides the files below a directory, for picking one to open or a place to save one
+#[derive(Debug, Clone)]
+pub struct FileProvider {
+    root: PathBuf,
+    directories: bool,
+    extensions: Vec<String>,
+    save_name: Option<String>,
+}
+
+impl FileProvider {
+    /// Pick from the files below `root`
+    pub fn new(root: PathBuf) -> Self {
+        FileProvider {
+            root,
+            directories: false,
+            extensions: vec![],
+            save_name: None,
+        }
+    }
+
+    /// Pick a directory rather than a file
+    pub fn directories(mut self, directories: bool) -> Self {
+        self.directories = directories;
+        self
+    }
+
+    /// Only offer files with one of these extensions, ignoring case.  All files when empty.
+    pub fn extensions(mut self, extensions: Vec<String>) -> Self {
+        self.extensions = extensions;
+        self
+    }
+
+    /// Pick where to save a file of this name, offering it in every directory.  Other names are
+    /// entered in the query.
+    pub fn save_name(mut self, name: Option<String>) -> Self {
+        self.save_name = name;
+        self
+    }
+
+    fn item(&self, path: PathBuf, is_dir: bool) -> FileItem {
+        let relative = path.strip_prefix(&self.root).unwrap_or(&path);
+        let title = match (relative.as_os_str().is_empty(), is_dir) {
+            (true, _) => String::from("./"),
+            (false, true) => format!("{}/", relative.display()),
+            (false, false) => relative.display().to_string(),
+        };
+        FileItem {
+            path,
+            title: common::intern(&title),
+        }
+    }
+
+    fn offers_file(&self, path: &Path) -> bool {
+        if self.directories || self.save_name.is_some() {
+            return false;
+        }
+        self.extensions.is_empty()
+            || path
+                .extension()
+                .and_then(|extension| extension.to_str())
+                .is_some_and(|extension| {
+                    self.extensions
+                        .iter()
+                        .any(|wanted| wanted.eq_ignore_ascii_case(extension))
+                })
+    }
+}
+
+impl ItemProvider for FileProvider {
+    type Item = FileItem;
+
+    fn load(&self) -> anyhow::Result<Vec<FileItem>> {
+        std::fs::read_dir(&self.root)
+            .with_context(|| format!("Unable to read {}", self.root.display()))?;
+
+        let mut items = vec![];
+        let mut pending = vec![(self.root.clone(), 0)];
+
+        while let Some((dir, depth)) = pending.pop() {
+            if self.directories {
+                items.push(self.item(dir.clone(), true));
+            }
+            if let Some(name) = &self.save_name {
+                items.push(self.item(dir.join(name), false));
+            }
+            // Skip directories which could not be read
+            let Ok(read_dir) = std::fs::read_dir(&dir) else {
+                continue;
+            };
+            let mut entries: Vec<_> = read_dir
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| !entry.file_name().to_string_lossy().starts_with('.'))
+                .collect();
+            entries.sort_by_key(|entry| entry.file_name());
+            // Popped from the end, so push subdirectories in reverse to list them in order
+            for entry in entries.into_iter().rev() {
+                let path = entry.path();
+                if entry.file_type().is_ok_and(|file_type| file_type.is_dir()) {
+                    if depth < MAX_DEPTH {
+                        pending.push((path, depth + 1));
+                    }
+                } else if self.offers_file(&path) {
+                    items.push(self.item(path, false));
+                }
+            }
+            if items.len() >= MAX_ITEMS {
+                break;
+            }
+        }
+        items.truncate(MAX_ITEMS);
+        Ok(items)
+    }
+
+    /// Save under the name entered in the query, relative to the root
+    fn create(&self, query: &str) -> Option<FileItem> {
+        self.save_name
+            .as_ref()
+            .map(|_| self.item(self.root.join(query), false))
+    }
+}
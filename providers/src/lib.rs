@@ -0,0 +1,33 @@
+//! Item providers shared by the ilia front-ends
+mod adjust;
+mod autostart;
+mod context;
+mod desktop;
+mod files;
+mod mpd;
+mod mpris;
+mod output;
+mod path;
+mod proc;
+mod repos;
+mod socket;
+mod stdin;
+mod sway;
+mod tool;
+mod toplevel;
+
+pub use adjust::{AdjustItem, AdjustProvider};
+pub use autostart::{AutostartItem, AutostartProvider};
+pub use context::{ContextItem, ContextProvider};
+pub use desktop::{DesktopEntryProvider, DesktopItem};
+pub use files::{FileItem, FileProvider};
+pub use mpd::{MpdProvider, TrackItem};
+pub use mpris::{MediaItem, MediaPlayerProvider};
+pub use output::OutputFormat;
+pub use path::{ExecutableItem, PathProvider};
+pub use proc::{ProcessItem, ProcessProvider};
+pub use repos::{RepoItem, RepoProvider};
+pub use socket::SocketProvider;
+pub use stdin::{Duplicates, LineItem, StdinProvider};
+pub use sway::{SwayWindowProvider, WindowItem};
+pub use toplevel::{ForeignToplevelProvider, ToplevelItem};
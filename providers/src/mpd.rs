@@ -0,0 +1,272 @@
+use std::borrow::Cow;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::os::unix::net::UnixStream;
+use std::process::exit;
+use std::sync::Arc;
+
+use anyhow::{bail, Context};
+use common::{Action, ItemDescriptor, ItemProvider};
+
+/// Port MPD listens on unless `MPD_PORT` says otherwise
+const DEFAULT_PORT: &str = "6600";
+/// Search results shown at once, the database may hold many thousands of tracks
+const MAX_RESULTS: usize = 200;
+
+/// A track in the MPD database
+#[derive(Debug, Clone)]
+pub struct TrackItem {
+    /// The track's URI within the database
+    file: Arc<str>,
+    title: Arc<str>,
+    album: Option<Arc<str>>,
+}
+
+impl TrackItem {
+    // Add the track to the end of the queue, returning its queue id
+    fn enqueue(&self, client: &mut Client) -> anyhow::Result<String> {
+        client
+            .command(&format!("addid {}", quote(&self.file)))?
+            .into_iter()
+            .find_map(|(key, value)| (key == "Id").then_some(value))
+            .context("MPD did not return the queue id")
+    }
+}
+
+impl ItemDescriptor for TrackItem {
+    fn title(&self) -> &str {
+        &self.title
+    }
+
+    fn id(&self) -> Cow<'_, str> {
+        Cow::Borrowed(&self.file)
+    }
+
+    fn subtitle(&self) -> Option<&str> {
+        self.album.as_deref()
+    }
+
+    /// Queue the track and play it right away
+    fn exec(&self) -> anyhow::Result<()> {
+        let mut client = Client::connect()?;
+        let id = self.enqueue(&mut client)?;
+        client.command(&format!("playid {}", id))?;
+
+        exit(0);
+    }
+}
+
+/// Searches the MPD database for tracks as the query changes
+#[derive(Debug, Default, Clone)]
+pub struct MpdProvider;
+
+impl ItemProvider for MpdProvider {
+    type Item = TrackItem;
+
+    /// Nothing is listed until there is a query
+    fn load(&self) -> anyhow::Result<Vec<TrackItem>> {
+        Ok(vec![])
+    }
+
+    fn is_queryable(&self) -> bool {
+        true
+    }
+
+    fn query(&self, query: &str) -> anyhow::Result<Vec<TrackItem>> {
+        let query = query.trim();
+        if query.is_empty() {
+            return Ok(vec![]);
+        }
+        let response = Client::connect()?.command(&format!(
+            "search any {} window 0:{}",
+            quote(query),
+            MAX_RESULTS
+        ))?;
+        Ok(tracks(response))
+    }
+
+    fn actions(&self, _item: &TrackItem) -> Vec<Action<TrackItem>> {
+        vec![Action {
+            id: "queue",
+            name: String::from("Add to queue"),
+            run: Arc::new(|item: &TrackItem| {
+                item.enqueue(&mut Client::connect()?)?;
+
+                exit(0);
+            }),
+        }]
+    }
+}
+
+/// A connection speaking the MPD text protocol
+trait Transport: Read + Write {}
+impl<T: Read + Write> Transport for T {}
+
+struct Client {
+    connection: BufReader<Box<dyn Transport>>,
+}
+
+impl Client {
+    /// Connect as mpc does, to `MPD_HOST` and `MPD_PORT`, where the host may be `password@host`
+    /// or the path of a local socket
+    fn connect() -> anyhow::Result<Client> {
+        let host = std::env::var("MPD_HOST").unwrap_or_else(|_| String::from("localhost"));
+        let port = std::env::var("MPD_PORT").unwrap_or_else(|_| String::from(DEFAULT_PORT));
+        let (password, host) = match host.rsplit_once('@') {
+            Some((password, host)) if !host.is_empty() => (Some(password.to_string()), host),
+            _ => (None, host.as_str()),
+        };
+
+        let transport: Box<dyn Transport> = if host.starts_with('/') {
+            Box::new(
+                UnixStream::connect(host)
+                    .with_context(|| format!("Can't connect to MPD at {}", host))?,
+            )
+        } else {
+            Box::new(
+                TcpStream::connect(format!("{}:{}", host, port))
+                    .with_context(|| format!("Can't connect to MPD at {}:{}", host, port))?,
+            )
+        };
+        let mut client = Client {
+            connection: BufReader::new(transport),
+        };
+
+        let mut greeting = String::new();
+        client.connection.read_line(&mut greeting)?;
+        if !greeting.starts_with("OK MPD") {
+            bail!("Not an MPD server: {}", greeting.trim());
+        }
+        if let Some(password) = password {
+            client.command(&format!("password {}", quote(&password)))?;
+        }
+        Ok(client)
+    }
+
+    /// Send a command, returning the key-value pairs of the response
+    fn command(&mut self, command: &str) -> anyhow::Result<Vec<(String, String)>> {
+        let connection = self.connection.get_mut();
+        connection.write_all(command.as_bytes())?;
+        connection.write_all(b"\n")?;
+
+        let mut pairs = vec![];
+        let mut line = String::new();
+        loop {
+            line.clear();
+            if self.connection.read_line(&mut line)? == 0 {
+                bail!("MPD closed the connection");
+            }
+            let line = line.trim_end_matches('\n');
+            if line == "OK" {
+                return Ok(pairs);
+            }
+            if let Some(error) = line.strip_prefix("ACK ") {
+                bail!("MPD refused {:?}: {}", command, error);
+            }
+            if let Some((key, value)) = line.split_once(": ") {
+                pairs.push((key.to_string(), value.to_string()));
+            }
+        }
+    }
+}
+
+/// Quote a command argument, escaping as the protocol requires
+fn quote(argument: &str) -> String {
+    format!(
+        "\"{}\"",
+        argument.replace('\\', "\\\\").replace('"', "\\\"")
+    )
+}
+
+// Group the pairs of a song list response into tracks, each starting at its "file" key
+fn tracks(pairs: Vec<(String, String)>) -> Vec<TrackItem> {
+    let mut songs: Vec<Vec<(String, String)>> = vec![];
+    for (key, value) in pairs {
+        if key == "file" {
+            songs.push(vec![]);
+        }
+        if let Some(song) = songs.last_mut() {
+            song.push((key, value));
+        }
+    }
+
+    songs
+        .into_iter()
+        .filter_map(|song| {
+            let tag = |name: &str| {
+                song.iter()
+                    .find(|(key, _)| key == name)
+                    .map(|(_, value)| value.as_str())
+            };
+            let file = tag("file")?;
+            let title = match (tag("Artist"), tag("Title")) {
+                (Some(artist), Some(title)) => format!("{} – {}", artist, title),
+                (None, Some(title)) => title.to_string(),
+                // Untagged files are known by their name
+                _ => file.rsplit('/').next().unwrap_or(file).to_string(),
+            };
+            Some(TrackItem {
+                file: common::intern(file),
+                title: common::intern(&title),
+                album: tag("Album").map(common::intern),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pairs(response: &str) -> Vec<(String, String)> {
+        response
+            .lines()
+            .filter_map(|line| line.split_once(": "))
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_quote() {
+        assert_eq!(quote("Abbey Road"), r#""Abbey Road""#);
+        assert_eq!(quote(r#"say "hi""#), r#""say \"hi\"""#);
+        assert_eq!(quote(r"AC\DC"), r#""AC\\DC""#);
+        // Backslashes are escaped before quotes, so the escapes of quotes are kept
+        assert_eq!(quote(r#"\""#), r#""\\\"""#);
+    }
+
+    #[test]
+    fn test_tracks() {
+        let response = "file: music/beatles/come_together.flac\n\
+            Artist: The Beatles\n\
+            Title: Come Together\n\
+            Album: Abbey Road\n\
+            Time: 259\n\
+            file: music/untagged/track01.mp3\n\
+            file: music/bach/prelude.ogg\n\
+            Title: Prelude in C\n";
+        let tracks = tracks(pairs(response));
+        let described: Vec<(&str, &str, Option<&str>)> = tracks
+            .iter()
+            .map(|track| (&*track.file, track.title(), track.subtitle()))
+            .collect();
+        assert_eq!(
+            described,
+            [
+                (
+                    "music/beatles/come_together.flac",
+                    "The Beatles – Come Together",
+                    Some("Abbey Road")
+                ),
+                ("music/untagged/track01.mp3", "track01.mp3", None),
+                ("music/bach/prelude.ogg", "Prelude in C", None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tracks_before_file() {
+        // Pairs before the first file belong to no track
+        assert!(tracks(pairs("Title: Orphan\n")).is_empty());
+    }
+}
@@ -0,0 +1,152 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::process::exit;
+use std::sync::Arc;
+
+use anyhow::Context;
+use common::{Action, ItemDescriptor, ItemProvider};
+use zbus::blocking::fdo::DBusProxy;
+use zbus::blocking::{Connection, Proxy};
+use zbus::zvariant::OwnedValue;
+
+/// Prefix of the bus names of every MPRIS player
+const MPRIS_PREFIX: &str = "org.mpris.MediaPlayer2.";
+const MPRIS_PATH: &str = "/org/mpris/MediaPlayer2";
+const PLAYER_INTERFACE: &str = "org.mpris.MediaPlayer2.Player";
+
+/// A media player on the session bus, and what it is playing
+#[derive(Debug, Clone)]
+pub struct MediaItem {
+    bus_name: Arc<str>,
+    title: Arc<str>,
+    /// The player's name and playback status
+    subtitle: Arc<str>,
+}
+
+impl MediaItem {
+    // Call a method of the player interface, such as "Next"
+    fn call(&self, method: &str) -> anyhow::Result<()> {
+        let connection = Connection::session().context("Can't connect to the session bus")?;
+        player_proxy(&connection, &self.bus_name)?
+            .call_method(method, &())
+            .with_context(|| format!("Unable to {} {}", method, self.bus_name))?;
+        Ok(())
+    }
+}
+
+impl ItemDescriptor for MediaItem {
+    fn title(&self) -> &str {
+        &self.title
+    }
+
+    fn id(&self) -> Cow<'_, str> {
+        Cow::Borrowed(&self.bus_name)
+    }
+
+    fn subtitle(&self) -> Option<&str> {
+        Some(&self.subtitle)
+    }
+
+    fn exec(&self) -> anyhow::Result<()> {
+        self.call("PlayPause")?;
+
+        exit(0);
+    }
+}
+
+/// Provides the MPRIS media players on the session bus
+#[derive(Debug, Default, Clone)]
+pub struct MediaPlayerProvider;
+
+impl ItemProvider for MediaPlayerProvider {
+    type Item = MediaItem;
+
+    fn load(&self) -> anyhow::Result<Vec<MediaItem>> {
+        let connection = Connection::session().context("Can't connect to the session bus")?;
+        let names = DBusProxy::new(&connection)?
+            .list_names()
+            .context("Can't list bus names")?;
+
+        let mut items = vec![];
+        for name in names.iter().filter(|name| name.starts_with(MPRIS_PREFIX)) {
+            // Players may exit while being listed
+            match player_item(&connection, name) {
+                Ok(item) => items.push(item),
+                Err(err) => eprintln!("Skipping {}: {:#}", name, err),
+            }
+        }
+        Ok(items)
+    }
+
+    fn actions(&self, _item: &MediaItem) -> Vec<Action<MediaItem>> {
+        [
+            ("next", "Next track", "Next"),
+            ("previous", "Previous track", "Previous"),
+        ]
+        .into_iter()
+        .map(|(id, name, method)| Action {
+            id,
+            name: String::from(name),
+            run: Arc::new(move |item: &MediaItem| {
+                item.call(method)?;
+
+                exit(0);
+            }),
+        })
+        .collect()
+    }
+}
+
+fn player_proxy<'a>(connection: &Connection, bus_name: &'a str) -> anyhow::Result<Proxy<'a>> {
+    Proxy::new(connection, bus_name, MPRIS_PATH, PLAYER_INTERFACE)
+        .with_context(|| format!("Can't reach player {}", bus_name))
+}
+
+// Describe the player at a bus name by its current track
+fn player_item(connection: &Connection, bus_name: &str) -> anyhow::Result<MediaItem> {
+    let player = player_proxy(connection, bus_name)?;
+    let status: String = player.get_property("PlaybackStatus")?;
+    let metadata: HashMap<String, OwnedValue> = player.get_property("Metadata")?;
+
+    let identity = Proxy::new(connection, bus_name, MPRIS_PATH, "org.mpris.MediaPlayer2")
+        .and_then(|proxy| proxy.get_property::<String>("Identity"))
+        .unwrap_or_else(|_| bus_name.trim_start_matches(MPRIS_PREFIX).to_string());
+
+    let track = metadata
+        .get("xesam:title")
+        .and_then(|title| String::try_from(title.clone()).ok())
+        .filter(|title| !title.is_empty());
+    let artists = metadata
+        .get("xesam:artist")
+        .and_then(|artists| Vec::<String>::try_from(artists.clone()).ok())
+        .unwrap_or_default();
+    let title = match (track, artists.is_empty()) {
+        (Some(track), false) => format!("{} – {}", track, artists.join(", ")),
+        (Some(track), true) => track,
+        // Nothing is loaded, so the player itself is the item
+        (None, _) => identity.clone(),
+    };
+
+    Ok(MediaItem {
+        bus_name: common::intern(bus_name),
+        title: common::intern(&title),
+        subtitle: common::intern(&format!("{} · {}", identity, status)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_actions() {
+        let item = MediaItem {
+            bus_name: Arc::from("org.mpris.MediaPlayer2.mpv"),
+            title: Arc::from("Song"),
+            subtitle: Arc::from("mpv · Playing"),
+        };
+        let actions = MediaPlayerProvider.actions(&item);
+        let ids: Vec<&str> = actions.iter().map(|action| action.id).collect();
+        assert_eq!(ids, ["next", "previous"]);
+    }
+}
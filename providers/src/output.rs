@@ -0,0 +1,140 @@
+//! How a selected line is written to standard output, so scripts can be given its position or a
+//! field of it rather than the text as listed
+use std::sync::OnceLock;
+
+use anyhow::bail;
+
+/// The format of the selected line, the line itself unless set
+static OUTPUT_FORMAT: OnceLock<OutputFormat> = OnceLock::new();
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Literal(String),
+    /// Position of the line in its input, from 0
+    Index,
+    /// The line as listed
+    Text,
+    /// The line after its first tab, or all of it
+    Value,
+}
+
+/// A template such as `{index}: {value}`, with `{{` and `}}` for literal braces
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutputFormat {
+    tokens: Vec<Token>,
+}
+
+impl OutputFormat {
+    pub fn parse(format: &str) -> anyhow::Result<OutputFormat> {
+        let mut tokens = vec![];
+        let mut literal = String::new();
+        let mut rest = format;
+        while let Some(c) = rest.chars().next() {
+            if let Some(after) = rest.strip_prefix("{{") {
+                literal.push('{');
+                rest = after;
+            } else if let Some(after) = rest.strip_prefix("}}") {
+                literal.push('}');
+                rest = after;
+            } else if c == '{' {
+                let Some(end) = rest.find('}') else {
+                    bail!("Unclosed {{ in output format {:?}", format);
+                };
+                let token = match &rest[1..end] {
+                    "index" => Token::Index,
+                    "text" => Token::Text,
+                    "value" => Token::Value,
+                    name => bail!("Unknown field {{{}}} in output format", name),
+                };
+                if !literal.is_empty() {
+                    tokens.push(Token::Literal(std::mem::take(&mut literal)));
+                }
+                tokens.push(token);
+                rest = &rest[end + 1..];
+            } else if c == '}' {
+                bail!("Unopened }} in output format {:?}", format);
+            } else {
+                literal.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+        if !literal.is_empty() {
+            tokens.push(Token::Literal(literal));
+        }
+        Ok(OutputFormat { tokens })
+    }
+
+    /// Write selected lines in this format from now on
+    pub fn install(self) {
+        let _ = OUTPUT_FORMAT.set(self);
+    }
+
+    fn format(&self, index: usize, text: &str) -> String {
+        self.tokens
+            .iter()
+            .map(|token| match token {
+                Token::Literal(literal) => literal.clone(),
+                Token::Index => index.to_string(),
+                Token::Text => text.to_string(),
+                Token::Value => text
+                    .split_once('\t')
+                    .map_or(text, |(_, value)| value)
+                    .to_string(),
+            })
+            .collect()
+    }
+}
+
+/// The output for the line at the index of its input, as the installed format says
+pub(crate) fn format(index: usize, text: &str) -> String {
+    match OUTPUT_FORMAT.get() {
+        Some(format) => format.format(index, text),
+        None => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn formatted(format: &str, index: usize, text: &str) -> String {
+        OutputFormat::parse(format).unwrap().format(index, text)
+    }
+
+    #[test]
+    fn test_placeholders() {
+        assert_eq!(formatted("{index}", 7, "firefox"), "7");
+        assert_eq!(
+            formatted("{text}", 7, "Firefox\tfirefox"),
+            "Firefox\tfirefox"
+        );
+        assert_eq!(formatted("{value}", 7, "Firefox\tfirefox"), "firefox");
+        // Only the first tab separates the value
+        assert_eq!(formatted("{value}", 7, "a\tb\tc"), "b\tc");
+        assert_eq!(formatted("{value}", 7, "no tab"), "no tab");
+        assert_eq!(formatted("{index}: {text} ✓", 2, "Files"), "2: Files ✓");
+    }
+
+    #[test]
+    fn test_tokens() {
+        assert_eq!(
+            OutputFormat::parse("#{index}{text}").unwrap().tokens,
+            [Token::Literal(String::from("#")), Token::Index, Token::Text]
+        );
+        assert!(OutputFormat::parse("").unwrap().tokens.is_empty());
+    }
+
+    #[test]
+    fn test_escapes() {
+        assert_eq!(formatted("{{{index}}}", 3, "line"), "{3}");
+        assert_eq!(formatted("{{text}}", 3, "line"), "{text}");
+        assert_eq!(formatted("}}{{", 3, "line"), "}{");
+    }
+
+    #[test]
+    fn test_malformed() {
+        for format in ["{name}", "{}", "{INDEX}", "{index", "index}", "{ index }"] {
+            assert!(OutputFormat::parse(format).is_err(), "{:?}", format);
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use std::collections::BTreeSet;
+use std::os::unix::fs::PermissionsExt;
+use std::process::exit;
+use std::sync::Arc;
+
+use anyhow::Context;
+use common::{ItemDescriptor, ItemProvider};
+
+/// An executable found on `PATH`
+#[derive(Debug, Clone)]
+pub struct ExecutableItem {
+    name: Arc<str>,
+    /// Arguments of a command line entered in the query
+    args: Vec<String>,
+}
+
+impl ItemDescriptor for ExecutableItem {
+    fn title(&self) -> &str {
+        &self.name
+    }
+
+    fn exec(&self) -> anyhow::Result<()> {
+        common::check_allowed(&shell_words::join(
+            std::iter::once(&*self.name).chain(self.args.iter().map(String::as_str)),
+        ))?;
+        common::command(&*self.name)
+            .args(&self.args)
+            .spawn()
+            .context("Failed to spawn app")
+            .map(|_| ())?;
+
+        exit(0);
+    }
+}
+
+/// Provides the executables in the directories of `PATH`, by name
+#[derive(Debug, Default, Clone)]
+pub struct PathProvider;
+
+impl ItemProvider for PathProvider {
+    type Item = ExecutableItem;
+
+    fn load(&self) -> anyhow::Result<Vec<ExecutableItem>> {
+        let path = std::env::var_os("PATH").context("PATH is not set")?;
+        let mut names: BTreeSet<Arc<str>> = BTreeSet::new();
+
+        for dir in std::env::split_paths(&path) {
+            // Skip directories which could not be read
+            let Ok(read_dir) = std::fs::read_dir(dir) else {
+                continue;
+            };
+            for entry in read_dir.filter_map(|entry| entry.ok()) {
+                let is_executable = entry.path().metadata().is_ok_and(|metadata| {
+                    metadata.is_file() && metadata.permissions().mode() & 0o111 != 0
+                });
+                if let (true, Some(name)) = (is_executable, entry.file_name().to_str()) {
+                    names.insert(common::intern(name));
+                }
+            }
+        }
+
+        Ok(names
+            .into_iter()
+            .map(|name| ExecutableItem { name, args: vec![] })
+            .collect())
+    }
+
+    /// Run the query as a command line, for commands which are not on `PATH` or need arguments
+    fn create(&self, query: &str) -> Option<ExecutableItem> {
+        let mut words = shell_words::split(query).ok()?.into_iter();
+        Some(ExecutableItem {
+            name: common::intern(&words.next()?),
+            args: words.collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_splits_command_line() {
+        let item = PathProvider.create("notify-send 'Hello there'").unwrap();
+        assert_eq!(item.title(), "notify-send");
+        assert_eq!(item.args, ["Hello there"]);
+        // Unbalanced quotes and empty queries are no command line
+        assert!(PathProvider.create("echo 'hi").is_none());
+        assert!(PathProvider.create("  ").is_none());
+    }
+}
@@ -0,0 +1,221 @@
+use std::borrow::Cow;
+use std::process::exit;
+use std::sync::Arc;
+
+use anyhow::{bail, Context};
+use common::{Action, ItemDescriptor, ItemProvider};
+
+/// A running process, titled by its name
+#[derive(Debug, Clone)]
+pub struct ProcessItem {
+    pid: libc::pid_t,
+    name: Arc<str>,
+    /// Its pid and what it uses of the CPU and memory
+    subtitle: Arc<str>,
+}
+
+impl ProcessItem {
+    fn signal(&self, signal: libc::c_int) -> anyhow::Result<()> {
+        // SAFETY: kill only reads its arguments
+        if unsafe { libc::kill(self.pid, signal) } != 0 {
+            bail!(
+                "Unable to signal {} ({}): {}",
+                self.name,
+                self.pid,
+                std::io::Error::last_os_error()
+            );
+        }
+        Ok(())
+    }
+}
+
+impl ItemDescriptor for ProcessItem {
+    fn title(&self) -> &str {
+        &self.name
+    }
+
+    fn id(&self) -> Cow<'_, str> {
+        Cow::Owned(self.pid.to_string())
+    }
+
+    fn subtitle(&self) -> Option<&str> {
+        Some(&self.subtitle)
+    }
+
+    fn exec(&self) -> anyhow::Result<()> {
+        self.signal(libc::SIGTERM)?;
+
+        exit(0);
+    }
+
+    fn confirmation(&self) -> Option<String> {
+        Some(format!("End {} ({})?", self.name, self.pid))
+    }
+}
+
+/// Provides the running processes from /proc, those using the most CPU first
+#[derive(Debug, Default, Clone)]
+pub struct ProcessProvider;
+
+impl ItemProvider for ProcessProvider {
+    type Item = ProcessItem;
+
+    fn load(&self) -> anyhow::Result<Vec<ProcessItem>> {
+        let uptime = std::fs::read_to_string("/proc/uptime").context("Unable to read uptime")?;
+        let uptime: f64 = uptime
+            .split_whitespace()
+            .next()
+            .and_then(|secs| secs.parse().ok())
+            .context("Unable to parse uptime")?;
+        // SAFETY: sysconf only reads its argument
+        let (ticks, page_size) = unsafe {
+            (
+                libc::sysconf(libc::_SC_CLK_TCK),
+                libc::sysconf(libc::_SC_PAGESIZE),
+            )
+        };
+        let own_pid = std::process::id() as libc::pid_t;
+
+        let mut processes = vec![];
+        for entry in std::fs::read_dir("/proc").context("Unable to read /proc")? {
+            let Some(pid) = entry
+                .ok()
+                .and_then(|entry| entry.file_name().to_str()?.parse::<libc::pid_t>().ok())
+            else {
+                continue;
+            };
+            // Processes may end while being listed
+            let Ok(stat) = std::fs::read_to_string(format!("/proc/{}/stat", pid)) else {
+                continue;
+            };
+            let Some(stat) = Stat::parse(&stat) else {
+                continue;
+            };
+            // Kernel threads have no memory of their own
+            if pid == own_pid || stat.rss_pages == 0 {
+                continue;
+            }
+            let running_secs = uptime - stat.start_ticks as f64 / ticks as f64;
+            let cpu = if running_secs > 0.0 {
+                100.0 * stat.cpu_ticks as f64 / ticks as f64 / running_secs
+            } else {
+                0.0
+            };
+            let mib = stat.rss_pages * page_size as u64 / (1024 * 1024);
+            processes.push((
+                cpu,
+                ProcessItem {
+                    pid,
+                    name: Arc::from(stat.name),
+                    subtitle: Arc::from(format!("pid {} · {:.1}% CPU · {} MiB", pid, cpu, mib)),
+                },
+            ));
+        }
+
+        processes.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+        Ok(processes.into_iter().map(|(_, item)| item).collect())
+    }
+
+    fn actions(&self, _item: &ProcessItem) -> Vec<Action<ProcessItem>> {
+        vec![Action {
+            id: "kill",
+            name: String::from("Kill"),
+            run: Arc::new(|item: &ProcessItem| {
+                item.signal(libc::SIGKILL)?;
+
+                exit(0);
+            }),
+        }]
+    }
+}
+
+/// The fields of /proc/<pid>/stat the listing shows
+#[derive(Debug, PartialEq)]
+struct Stat<'a> {
+    name: &'a str,
+    /// Time spent in user and kernel mode, in clock ticks
+    cpu_ticks: u64,
+    /// When the process started after boot, in clock ticks
+    start_ticks: u64,
+    /// Resident memory, in pages
+    rss_pages: u64,
+}
+
+impl<'a> Stat<'a> {
+    fn parse(stat: &'a str) -> Option<Stat<'a>> {
+        // The name is in parentheses and may hold any of them, so it ends at the last one
+        let (_, rest) = stat.split_once('(')?;
+        let end = rest.rfind(')')?;
+        let name = &rest[..end];
+        // Fields counted from the state, the third in proc(5)
+        let fields: Vec<&str> = rest[end + 1..].split_whitespace().collect();
+        let field = |index: usize| fields.get(index)?.parse::<u64>().ok();
+        Some(Stat {
+            name,
+            cpu_ticks: field(11)? + field(12)?,
+            start_ticks: field(19)?,
+            rss_pages: field(21)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A stat line as proc(5) lays it out, with the fields the listing shows set
+    fn stat_line(comm: &str) -> String {
+        format!(
+            "4242 ({}) S 1 4242 4242 0 -1 4194560 1500 0 3 0 120 30 0 0 20 0 4 0 98765 \
+             1073741824 2048 18446744073709551615 1 1 0 0 0 0 0 4096 17663 0 0 0 17 3 0 0",
+            comm
+        )
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            Stat::parse(&stat_line("firefox")),
+            Some(Stat {
+                name: "firefox",
+                cpu_ticks: 150,
+                start_ticks: 98765,
+                rss_pages: 2048,
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_name_with_spaces_and_parentheses() {
+        for comm in ["Web Content", "(sd-pam)", "a) S 1 (b", "tmux: server)", ""] {
+            let line = stat_line(comm);
+            let stat = Stat::parse(&line).unwrap();
+            assert_eq!(stat.name, comm);
+            assert_eq!(stat.cpu_ticks, 150);
+            assert_eq!(stat.rss_pages, 2048);
+        }
+    }
+
+    #[test]
+    fn test_actions() {
+        let item = ProcessItem {
+            pid: 4242,
+            name: Arc::from("firefox"),
+            subtitle: Arc::from("pid 4242"),
+        };
+        let actions = ProcessProvider.actions(&item);
+        let ids: Vec<&str> = actions.iter().map(|action| action.id).collect();
+        assert_eq!(ids, ["kill"]);
+    }
+
+    #[test]
+    fn test_parse_malformed() {
+        assert_eq!(Stat::parse(""), None);
+        assert_eq!(Stat::parse("4242 firefox S 1"), None);
+        assert_eq!(Stat::parse("4242 (firefox S 1"), None);
+        // Cut short before the resident memory
+        let line = stat_line("firefox");
+        let cut = &line[..line.find(" 1073741824").unwrap()];
+        assert_eq!(Stat::parse(cut), None);
+    }
+}
@@ -0,0 +1,217 @@
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
+use std::process::exit;
+use std::sync::Arc;
+
+use anyhow::Context;
+use common::{Action, ItemDescriptor, ItemProvider};
+
+use crate::tool::run;
+
+/// Levels of directories below a root searched for repositories
+const MAX_DEPTH: usize = 4;
+
+/// A git repository, titled by its path below the root it was found in
+#[derive(Debug, Clone)]
+pub struct RepoItem {
+    path: PathBuf,
+    title: Arc<str>,
+}
+
+impl RepoItem {
+    fn path(&self) -> anyhow::Result<&str> {
+        self.path.to_str().context("Repository path is not text")
+    }
+}
+
+impl ItemDescriptor for RepoItem {
+    fn title(&self) -> &str {
+        &self.title
+    }
+
+    fn id(&self) -> Cow<'_, str> {
+        self.path.to_string_lossy()
+    }
+
+    /// Open the repository in the default file manager
+    fn exec(&self) -> anyhow::Result<()> {
+        common::command("xdg-open")
+            .arg(&self.path)
+            .spawn()
+            .context("Failed to open repository")
+            .map(|_| ())?;
+
+        exit(0);
+    }
+}
+
+/// Provides the git repositories below some directories, to open one or act on it
+#[derive(Debug, Clone)]
+pub struct RepoProvider {
+    roots: Vec<PathBuf>,
+}
+
+impl RepoProvider {
+    /// Find the repositories below each of `roots`
+    pub fn new(roots: Vec<PathBuf>) -> Self {
+        RepoProvider { roots }
+    }
+}
+
+impl ItemProvider for RepoProvider {
+    type Item = RepoItem;
+
+    fn load(&self) -> anyhow::Result<Vec<RepoItem>> {
+        let mut items = vec![];
+        for root in &self.roots {
+            std::fs::read_dir(root)
+                .with_context(|| format!("Unable to read {}", root.display()))?;
+            items.extend(find_repos(root).into_iter().map(|path| {
+                let relative = path.strip_prefix(root).unwrap_or(&path);
+                // A root which is a repository itself is titled by its full path
+                let title = if relative.as_os_str().is_empty() {
+                    path.display().to_string()
+                } else {
+                    relative.display().to_string()
+                };
+                RepoItem {
+                    title: common::intern(&title),
+                    path,
+                }
+            }));
+        }
+        Ok(items)
+    }
+
+    fn actions(&self, _item: &RepoItem) -> Vec<Action<RepoItem>> {
+        vec![
+            Action {
+                id: "lazygit",
+                name: String::from("Open in lazygit"),
+                run: Arc::new(|item: &RepoItem| {
+                    // The terminal of the user's choice, which all take a command after -e
+                    let terminal =
+                        std::env::var("TERMINAL").unwrap_or_else(|_| String::from("xterm"));
+                    common::command(&terminal)
+                        .args(["-e", "lazygit", "--path", item.path()?])
+                        .spawn()
+                        .with_context(|| format!("Failed to run {}", terminal))?;
+
+                    exit(0);
+                }),
+            },
+            Action {
+                id: "copy",
+                name: String::from("Copy path"),
+                run: Arc::new(|item: &RepoItem| {
+                    common::copy_to_clipboard(item.path()?)?;
+
+                    exit(0);
+                }),
+            },
+            Action {
+                id: "open-remote",
+                name: String::from("Open remote"),
+                run: Arc::new(|item: &RepoItem| {
+                    let remote = run("git", &["-C", item.path()?, "remote", "get-url", "origin"])?;
+                    common::command("xdg-open")
+                        .arg(web_url(remote.trim()))
+                        .spawn()
+                        .context("Failed to open remote")?;
+
+                    exit(0);
+                }),
+            },
+        ]
+    }
+}
+
+// The repositories at or below the directory, in order, not looking inside them or hidden
+// directories for more
+fn find_repos(root: &Path) -> Vec<PathBuf> {
+    let mut repos = vec![];
+    let mut pending = vec![(root.to_path_buf(), 0)];
+
+    while let Some((dir, depth)) = pending.pop() {
+        if dir.join(".git").exists() {
+            repos.push(dir);
+            continue;
+        }
+        // Skip directories which could not be read
+        let Ok(read_dir) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        let mut subdirs: Vec<PathBuf> = read_dir
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| !entry.file_name().to_string_lossy().starts_with('.'))
+            .filter(|entry| entry.file_type().is_ok_and(|file_type| file_type.is_dir()))
+            .map(|entry| entry.path())
+            .collect();
+        subdirs.sort();
+        // Popped from the end, so push subdirectories in reverse to list them in order
+        if depth < MAX_DEPTH {
+            pending.extend(subdirs.into_iter().rev().map(|path| (path, depth + 1)));
+        }
+    }
+    repos
+}
+
+// The web page of a remote, from its URL as git fetches it: "git@github.com:owner/repo.git"
+// becomes "https://github.com/owner/repo"
+fn web_url(remote: &str) -> String {
+    let remote = remote.strip_suffix(".git").unwrap_or(remote);
+    if let Some(rest) = remote.strip_prefix("ssh://") {
+        let rest = rest.split_once('@').map_or(rest, |(_, host)| host);
+        return format!("https://{}", rest);
+    }
+    match remote
+        .split_once('@')
+        .and_then(|(_, rest)| rest.split_once(':'))
+    {
+        Some((host, path)) if !remote.contains("://") => format!("https://{}/{}", host, path),
+        _ => remote.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_web_url() {
+        assert_eq!(
+            web_url("git@github.com:kgilmer/ilia3.git"),
+            "https://github.com/kgilmer/ilia3"
+        );
+        assert_eq!(
+            web_url("ssh://git@codeberg.org/owner/repo.git"),
+            "https://codeberg.org/owner/repo"
+        );
+        assert_eq!(
+            web_url("https://github.com/kgilmer/ilia3.git"),
+            "https://github.com/kgilmer/ilia3"
+        );
+    }
+
+    #[test]
+    fn test_find_repos() {
+        let root = std::env::temp_dir().join(format!("ilia-test-repos-{}", std::process::id()));
+        for dir in [
+            "b/.git",
+            "a/.git/sub",
+            "a/nested/.git",
+            "c/d/.git",
+            ".hidden/e/.git",
+        ] {
+            std::fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        let repos = find_repos(&root);
+        std::fs::remove_dir_all(&root).unwrap();
+
+        // Repositories inside repositories, and in hidden directories, are left out
+        assert_eq!(
+            repos,
+            [root.join("a"), root.join("b"), root.join("c").join("d")]
+        );
+    }
+}
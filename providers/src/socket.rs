@@ -0,0 +1,253 @@
+//! Lines sent over a Unix socket by other processes while the picker is open.  Each connection
+//! sends a command line, then the item lines it applies to, then closes, within two seconds:
+//!
+//! ```text
+//! replace        the items become the following lines
+//! append         the following lines are added to the items
+//! clear          there are no items anymore
+//! ```
+//!
+//! For example `printf 'append\nNew item\n' | socat - UNIX-CONNECT:$XDG_RUNTIME_DIR/ilia/dmenu.sock`
+use std::io::{BufRead, BufReader};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{bail, Context};
+use common::{ItemProvider, ItemStream};
+use futures::channel::mpsc;
+use futures::StreamExt;
+
+use crate::LineItem;
+
+/// How long a connection may take to send its message, before it is dropped for the next one
+const READ_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// A command read from a connection, with the non-empty lines it applies to
+#[derive(Debug, PartialEq)]
+enum Message {
+    Replace(Vec<String>),
+    Append(Vec<String>),
+    Clear,
+}
+
+/// Provides lines, initially given and then changed by commands received on a Unix socket
+#[derive(Debug)]
+pub struct SocketProvider {
+    items: Arc<Mutex<Vec<LineItem>>>,
+    /// Taken by the first poll of the update stream
+    listener: Arc<Mutex<Option<UnixListener>>>,
+}
+
+impl SocketProvider {
+    /// Default socket location, in the user's runtime directory
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::runtime_dir().map(|dir| dir.join("ilia").join("dmenu.sock"))
+    }
+
+    /// Listen on the socket at `path`, starting with the given lines
+    pub fn bind(path: &Path, lines: Vec<String>) -> anyhow::Result<SocketProvider> {
+        if path.exists() {
+            // A socket left behind by a picker which has exited is replaced
+            if UnixStream::connect(path).is_ok() {
+                bail!("Another picker is listening on {}", path.display());
+            }
+            std::fs::remove_file(path).context("Failed to remove stale socket")?;
+        }
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).context("Failed to create socket dir")?;
+        }
+        let listener = UnixListener::bind(path)
+            .with_context(|| format!("Failed to listen on {}", path.display()))?;
+
+        Ok(SocketProvider {
+            items: Arc::new(Mutex::new(
+                lines
+                    .iter()
+                    .enumerate()
+                    .map(|(index, line)| LineItem::new(index, line))
+                    .collect(),
+            )),
+            listener: Arc::new(Mutex::new(Some(listener))),
+        })
+    }
+}
+
+impl ItemProvider for SocketProvider {
+    type Item = LineItem;
+
+    fn load(&self) -> anyhow::Result<Vec<LineItem>> {
+        Ok(self
+            .items
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .clone())
+    }
+
+    fn watch(&self) -> Option<ItemStream<LineItem>> {
+        let items = self.items.clone();
+        let listener = self.listener.clone();
+        let updates = futures::stream::once(async move {
+            let (sender, receiver) = mpsc::unbounded();
+            let listener = listener
+                .lock()
+                .unwrap_or_else(|err| err.into_inner())
+                .take();
+            // Accepting blocks, so connections are served on a thread of their own
+            if let Some(listener) = listener {
+                std::thread::spawn(move || serve(listener, items, sender));
+            }
+            receiver
+        })
+        .flatten();
+        Some(updates.boxed())
+    }
+}
+
+// Apply the commands of each connection in turn, sending the resulting items.  The items are
+// only locked once a whole message is read, so a slow client never holds up `load`.
+fn serve(
+    listener: UnixListener,
+    items: Arc<Mutex<Vec<LineItem>>>,
+    sender: mpsc::UnboundedSender<Vec<LineItem>>,
+) {
+    for stream in listener.incoming() {
+        let result = stream
+            .context("Failed to accept connection")
+            .and_then(|stream| {
+                stream
+                    .set_read_timeout(Some(READ_TIMEOUT))
+                    .context("Failed to set read timeout")?;
+                read(BufReader::new(stream))
+            })
+            .map(|message| {
+                let mut items = items.lock().unwrap_or_else(|err| err.into_inner());
+                apply(message, &mut items);
+                items.clone()
+            });
+        match result {
+            Ok(items) => {
+                // The UI has gone away
+                if sender.unbounded_send(items).is_err() {
+                    return;
+                }
+            }
+            Err(err) => eprintln!("Ignoring message: {:#}", err),
+        }
+    }
+}
+
+// Read the whole message of a connection
+fn read(reader: impl BufRead) -> anyhow::Result<Message> {
+    let mut lines = reader.lines();
+    let command = lines.next().context("Empty message")??;
+    let lines = lines
+        .filter(|line| !line.as_ref().is_ok_and(String::is_empty))
+        .collect::<Result<Vec<String>, _>>()
+        .context("Failed to read items")?;
+
+    match command.trim() {
+        "replace" => Ok(Message::Replace(lines)),
+        "append" => Ok(Message::Append(lines)),
+        "clear" => Ok(Message::Clear),
+        other => bail!("Unknown command {:?}", other),
+    }
+}
+
+// Change the items as the message says, numbering received lines on from those they follow
+fn apply(message: Message, items: &mut Vec<LineItem>) {
+    let received = |offset: usize, lines: Vec<String>| {
+        lines
+            .into_iter()
+            .enumerate()
+            .map(move |(index, line)| LineItem::new(offset + index, &line))
+    };
+    match message {
+        Message::Replace(lines) => *items = received(0, lines).collect(),
+        Message::Append(lines) => {
+            let offset = items.len();
+            items.extend(received(offset, lines));
+        }
+        Message::Clear => items.clear(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use common::ItemDescriptor;
+    use futures::executor::block_on;
+
+    use super::*;
+
+    fn titles(items: &[LineItem]) -> Vec<&str> {
+        items.iter().map(|item| item.title()).collect()
+    }
+
+    fn indices(items: &[LineItem]) -> Vec<usize> {
+        items.iter().map(|item| item.index).collect()
+    }
+
+    fn apply_read(message: &[u8], items: &mut Vec<LineItem>) {
+        apply(read(message).unwrap(), items);
+    }
+
+    #[test]
+    fn test_apply_commands() {
+        let mut items = vec![LineItem::new(0, "first")];
+        apply_read(b"append\nsecond\n\nthird\n", &mut items);
+        assert_eq!(titles(&items), ["first", "second", "third"]);
+        // Appended lines are numbered on from the items they follow
+        assert_eq!(indices(&items), [0, 1, 2]);
+
+        apply_read(b"replace\n\nonly\nnext\n", &mut items);
+        assert_eq!(titles(&items), ["only", "next"]);
+        // Replacing lines are numbered without the empty lines left out
+        assert_eq!(indices(&items), [0, 1]);
+
+        // Surrounding whitespace and a missing final newline are allowed
+        apply_read(b" clear \r\n", &mut items);
+        assert!(items.is_empty());
+    }
+
+    #[test]
+    fn test_read_malformed() {
+        assert!(read(&b""[..]).is_err());
+        assert!(read(&b"remove\nkept\n"[..]).is_err());
+        // Items must be text
+        assert!(read(&b"append\n\xff\xfe\n"[..]).is_err());
+        assert_eq!(
+            read(&b"append\n\nkept\n"[..]).unwrap(),
+            Message::Append(vec![String::from("kept")])
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let path = std::env::temp_dir().join(format!("ilia-test-{}.sock", std::process::id()));
+        let provider = SocketProvider::bind(&path, vec![String::from("first")]).unwrap();
+        // A second picker on the same socket is refused
+        assert!(SocketProvider::bind(&path, vec![]).is_err());
+
+        let mut updates = provider.watch().unwrap();
+        // The socket is bound already, so the connection waits to be accepted
+        let mut stream = UnixStream::connect(&path).unwrap();
+        stream.write_all(b"append\nsecond\n").unwrap();
+        drop(stream);
+        let received = block_on(updates.next()).unwrap();
+        assert_eq!(titles(&received), ["first", "second"]);
+
+        // A client which has not finished its message leaves the items free to load
+        let mut idle = UnixStream::connect(&path).unwrap();
+        idle.write_all(b"append\n").unwrap();
+        assert_eq!(titles(&provider.load().unwrap()), ["first", "second"]);
+        drop(idle);
+        let received = block_on(updates.next()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(titles(&received), ["first", "second"]);
+        assert_eq!(titles(&provider.load().unwrap()), ["first", "second"]);
+    }
+}
@@ -0,0 +1,324 @@
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::io::{BufRead, BufReader, Read, StdinLock};
+use std::process::exit;
+use std::sync::Arc;
+
+use common::{ItemBatches, ItemDescriptor, ItemProvider};
+use futures::channel::mpsc;
+use futures::StreamExt;
+
+/// Lines of standard input are read into shared chunks of about this many bytes, so huge inputs
+/// need no allocation per line
+const CHUNK_LEN: usize = 1 << 20;
+
+/// Bytes of standard input read at a time
+const READ_LEN: usize = 1 << 16;
+
+/// The most batches of lines the UI is sent at once
+const BATCHES_PER_APPEND: usize = 64;
+
+/// A line of text read from standard input
+#[derive(Debug, Clone)]
+pub struct LineItem {
+    /// The text the line was read with, shared by all its lines
+    text: Arc<str>,
+    /// Byte offsets of the line within the text
+    start: usize,
+    end: usize,
+    /// Position of the line in the input it was read from, from 0
+    pub(crate) index: usize,
+    /// How often the line was read, when duplicates are counted and it was read more than once
+    badge: Option<Arc<str>>,
+}
+
+impl LineItem {
+    pub(crate) fn new(index: usize, line: &str) -> Self {
+        let text = common::intern(line);
+        LineItem {
+            end: text.len(),
+            text,
+            start: 0,
+            index,
+            badge: None,
+        }
+    }
+}
+
+/// Read the non-empty lines of the reader, as `BufRead::lines` splits them, passing them to
+/// `batch` whenever a chunk fills or the reader has nothing more buffered, so lines are not held
+/// back while the writer is slow.  Stops early when `batch` returns false.
+fn read_lines<R: Read>(
+    mut reader: BufReader<R>,
+    mut batch: impl FnMut(Vec<LineItem>) -> bool,
+) -> anyhow::Result<()> {
+    let mut chunk = String::with_capacity(CHUNK_LEN);
+    let mut lines = vec![];
+    let mut index = 0;
+    loop {
+        let start = chunk.len();
+        let read = reader.read_line(&mut chunk)?;
+        let line = chunk[start..].strip_suffix('\n').unwrap_or(&chunk[start..]);
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        if !line.is_empty() {
+            lines.push((start, start + line.len(), index));
+        }
+        index += 1;
+        if read == 0 || chunk.len() >= CHUNK_LEN || reader.buffer().is_empty() {
+            if !lines.is_empty() {
+                let text: Arc<str> = Arc::from(chunk.as_str());
+                let items = lines
+                    .drain(..)
+                    .map(|(start, end, index)| LineItem {
+                        text: text.clone(),
+                        start,
+                        end,
+                        index,
+                        badge: None,
+                    })
+                    .collect();
+                if !batch(items) {
+                    return Ok(());
+                }
+            }
+            chunk.clear();
+        }
+        if read == 0 {
+            return Ok(());
+        }
+    }
+}
+
+impl ItemDescriptor for LineItem {
+    fn title(&self) -> &str {
+        &self.text[self.start..self.end]
+    }
+
+    fn badge(&self) -> Option<&str> {
+        self.badge.as_deref()
+    }
+
+    /// Selecting a line writes it to standard output, as dmenu does, in the output format
+    fn exec(&self) -> anyhow::Result<()> {
+        common::check_allowed(self.title())?;
+        println!("{}", crate::output::format(self.index, self.title()));
+
+        exit(0);
+    }
+}
+
+/// What becomes of lines read more than once
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Duplicates {
+    /// Every line is listed
+    #[default]
+    Keep,
+    /// Equal lines are listed once, where the first was read
+    Collapse,
+    /// Equal lines are listed once, where the first was read, badged with how many were read
+    Count,
+}
+
+/// Provides the non-empty lines of standard input
+#[derive(Debug, Default, Clone)]
+pub struct StdinProvider {
+    duplicates: Duplicates,
+}
+
+impl StdinProvider {
+    pub fn new(duplicates: Duplicates) -> Self {
+        StdinProvider { duplicates }
+    }
+}
+
+impl ItemProvider for StdinProvider {
+    type Item = LineItem;
+
+    fn load(&self) -> anyhow::Result<Vec<LineItem>> {
+        let mut items = vec![];
+        let mut seen = Seen::default();
+        read_lines(stdin_reader(), |mut batch| {
+            if self.duplicates != Duplicates::Keep {
+                seen.retain_first(&mut batch);
+            }
+            items.extend(batch);
+            true
+        })?;
+        if self.duplicates == Duplicates::Count {
+            seen.badge(&mut items);
+        }
+        Ok(items)
+    }
+
+    /// Lines are read on a thread of their own, so the first are listed while a slow pipeline
+    /// such as `find /` is still writing.  Counts are only known once the input ends, so counted
+    /// lines are loaded at once.
+    fn load_incremental(&self) -> Option<ItemBatches<LineItem>> {
+        if self.duplicates == Duplicates::Count {
+            return None;
+        }
+        let collapse = self.duplicates == Duplicates::Collapse;
+        let (sender, receiver) = mpsc::unbounded();
+        std::thread::spawn(move || {
+            let mut seen = Seen::default();
+            // The UI has gone away when sending fails
+            let result = read_lines(stdin_reader(), |mut batch| {
+                if collapse {
+                    seen.retain_first(&mut batch);
+                }
+                batch.is_empty() || sender.unbounded_send(Ok(batch)).is_ok()
+            });
+            if let Err(err) = result {
+                let _ = sender.unbounded_send(Err(err.context("Failed to read standard input")));
+            }
+        });
+        // Batches which arrive while the UI is busy are appended together
+        let batches = receiver.ready_chunks(BATCHES_PER_APPEND).map(|batches| {
+            let mut items = vec![];
+            for batch in batches {
+                items.extend(batch?);
+            }
+            Ok(items)
+        });
+        Some(batches.boxed())
+    }
+
+    /// Standard input is read once, so the lines stay as they are
+    fn refresh(&self, current: &[LineItem]) -> anyhow::Result<Vec<LineItem>> {
+        Ok(current.to_vec())
+    }
+}
+
+/// A line compared and hashed by its text
+#[derive(Debug)]
+struct Line(LineItem);
+
+impl PartialEq for Line {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.title() == other.0.title()
+    }
+}
+
+impl Eq for Line {}
+
+// As `str` hashes, so lines can be looked up by their text
+impl Hash for Line {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.title().hash(state)
+    }
+}
+
+impl Borrow<str> for Line {
+    fn borrow(&self) -> &str {
+        self.0.title()
+    }
+}
+
+/// How often each line has been read
+#[derive(Debug, Default)]
+struct Seen {
+    counts: HashMap<Line, usize>,
+}
+
+impl Seen {
+    // Leave out the lines of the batch read before, counting them
+    fn retain_first(&mut self, batch: &mut Vec<LineItem>) {
+        batch.retain(|item| match self.counts.get_mut(item.title()) {
+            Some(count) => {
+                *count += 1;
+                false
+            }
+            None => {
+                self.counts.insert(Line(item.clone()), 1);
+                true
+            }
+        });
+    }
+
+    // Badge the lines read more than once with their count
+    fn badge(&self, items: &mut [LineItem]) {
+        for item in items {
+            let count = self.counts.get(item.title()).copied().unwrap_or(1);
+            if count > 1 {
+                item.badge = Some(common::intern(&format!("×{}", count)));
+            }
+        }
+    }
+}
+
+// Standard input, buffered in pieces small enough that lines show up soon after they are written
+fn stdin_reader() -> BufReader<StdinLock<'static>> {
+    BufReader::with_capacity(READ_LEN, std::io::stdin().lock())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The lines of the input in the batches they were read in
+    fn batches(input: &str) -> Vec<Vec<LineItem>> {
+        let mut batches = vec![];
+        read_lines(BufReader::new(input.as_bytes()), |batch| {
+            batches.push(batch);
+            true
+        })
+        .unwrap();
+        batches
+    }
+
+    #[test]
+    fn test_read_lines() {
+        let lines: Vec<(usize, String)> = batches("one\r\n\ntwo\nthree")
+            .concat()
+            .iter()
+            .map(|item| (item.index, item.title().to_string()))
+            .collect();
+        // Empty lines are left out, but still counted for the output index
+        assert_eq!(
+            lines,
+            [
+                (0, String::from("one")),
+                (2, String::from("two")),
+                (3, String::from("three"))
+            ]
+        );
+    }
+
+    #[test]
+    fn test_collapse_duplicates() {
+        let mut seen = Seen::default();
+        let mut first = vec![
+            LineItem::new(0, "a"),
+            LineItem::new(1, "b"),
+            LineItem::new(2, "a"),
+        ];
+        let mut second = vec![LineItem::new(3, "b"), LineItem::new(4, "c")];
+        seen.retain_first(&mut first);
+        // Lines read in an earlier batch are duplicates too
+        seen.retain_first(&mut second);
+        let kept: Vec<(usize, &str)> = first
+            .iter()
+            .chain(&second)
+            .map(|item| (item.index, item.title()))
+            .collect();
+        assert_eq!(kept, [(0, "a"), (1, "b"), (4, "c")]);
+    }
+
+    #[test]
+    fn test_count_duplicates() {
+        let mut seen = Seen::default();
+        let mut items: Vec<LineItem> = ["a", "b", "a", "a"]
+            .iter()
+            .enumerate()
+            .map(|(index, line)| LineItem::new(index, line))
+            .collect();
+        seen.retain_first(&mut items);
+        seen.badge(&mut items);
+        let badges: Vec<(&str, Option<&str>)> = items
+            .iter()
+            .map(|item| (item.title(), item.badge()))
+            .collect();
+        assert_eq!(badges, [("a", Some("×3")), ("b", None)]);
+    }
+}
@@ -0,0 +1,260 @@
+use std::borrow::Cow;
+use std::process::exit;
+use std::sync::Arc;
+
+use anyhow::Context;
+use common::{Action, ItemDescriptor, ItemProvider};
+use swayipc::{Connection, Node, NodeLayout, NodeType, ShellType};
+
+// MAYDO: refactor for i3 compat
+const IS_WAYLAND: bool = true;
+
+/// Badge of windows running under XWayland
+const XWAYLAND_BADGE: &str = "[X]";
+
+/// A window managed by sway
+#[derive(Debug, Clone)]
+pub struct WindowItem {
+    id: i64,
+    title: Arc<str>,
+    /// The Wayland app id, or the X11 class of XWayland windows, naming its icon
+    app_id: Option<Arc<str>>,
+    /// The app id and the workspace the window is on
+    subtitle: Option<Arc<str>>,
+    workspace: Option<Arc<str>>,
+    /// Size and position on the output, as `WIDTHxHEIGHT+X+Y`
+    geometry: Arc<str>,
+    /// Whether the window is an X11 client, which renders blurry under fractional scaling
+    xwayland: bool,
+}
+
+impl ItemDescriptor for WindowItem {
+    fn title(&self) -> &str {
+        &self.title
+    }
+
+    fn id(&self) -> Cow<'_, str> {
+        Cow::Owned(self.id.to_string())
+    }
+
+    fn subtitle(&self) -> Option<&str> {
+        self.subtitle.as_deref()
+    }
+
+    fn icon(&self) -> Option<&str> {
+        self.app_id.as_deref()
+    }
+
+    fn badge(&self) -> Option<&str> {
+        self.xwayland.then_some(XWAYLAND_BADGE)
+    }
+
+    fn details(&self) -> Vec<(&'static str, String)> {
+        [
+            ("Workspace", self.workspace.as_deref()),
+            ("App id", self.app_id.as_deref()),
+            ("Geometry", Some(&*self.geometry)),
+        ]
+        .into_iter()
+        .filter_map(|(label, value)| Some((label, value?.to_string())))
+        .collect()
+    }
+
+    fn exec(&self) -> anyhow::Result<()> {
+        self.command("focus")?;
+
+        exit(0);
+    }
+}
+
+impl WindowItem {
+    /// Run a sway command against this window, failing if sway rejects it
+    fn command(&self, command: &str) -> anyhow::Result<()> {
+        let outcomes = Connection::new()
+            .context("Can't connect to WM socket")?
+            .run_command(format!("[con_id={}] {}", self.id, command))
+            .context("Failed to send command")?;
+
+        // A window that vanished since loading yields an error rather than a failed IPC
+        for outcome in outcomes {
+            outcome.with_context(|| format!("Unable to {} {}", command, self.title))?;
+        }
+        Ok(())
+    }
+}
+
+impl WindowItem {
+    /// The window of the node, on the named workspace
+    fn new(node: Node, workspace: Option<&str>) -> Self {
+        let app_id = node
+            .app_id
+            .or_else(|| {
+                node.window_properties
+                    .and_then(|properties| properties.class)
+            })
+            .map(|app_id| common::intern(&app_id));
+        // Some X11 and transient windows have no name, and are known by their app
+        let title = node
+            .name
+            .or_else(|| app_id.as_deref().map(String::from))
+            .unwrap_or_default();
+
+        let xwayland = match node.shell {
+            Some(shell) => shell == ShellType::Xwayland,
+            None => node.window.is_some(),
+        };
+
+        let rect = node.rect;
+        let geometry = format!("{}x{}+{}+{}", rect.width, rect.height, rect.x, rect.y);
+        let subtitle = [app_id.as_deref(), workspace]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(" · ");
+
+        WindowItem {
+            id: node.id,
+            title: common::intern(&title),
+            subtitle: (!subtitle.is_empty()).then(|| common::intern(&subtitle)),
+            app_id,
+            workspace: workspace.map(common::intern),
+            geometry: Arc::from(geometry),
+            xwayland,
+        }
+    }
+}
+
+/// Provides the windows in the sway tree
+#[derive(Debug, Default, Clone)]
+pub struct SwayWindowProvider {
+    /// Kill the marked windows rather than gather them to the focused workspace
+    kill_marked: bool,
+}
+
+impl SwayWindowProvider {
+    /// Kill the marked windows when they are taken, rather than gather them
+    pub fn kill_marked(mut self, kill_marked: bool) -> Self {
+        self.kill_marked = kill_marked;
+        self
+    }
+
+    /// Whether a sway or i3 IPC socket is advertised in the environment
+    pub fn is_available() -> bool {
+        std::env::var_os("SWAYSOCK").is_some() || std::env::var_os("I3SOCK").is_some()
+    }
+}
+
+impl ItemProvider for SwayWindowProvider {
+    type Item = WindowItem;
+
+    fn load(&self) -> anyhow::Result<Vec<WindowItem>> {
+        if !Self::is_available() {
+            anyhow::bail!(
+                "Neither SWAYSOCK nor I3SOCK is set, ilia-windows needs a running sway or i3"
+            );
+        }
+        let root_node = Connection::new()
+            .context("Can't connect to WM socket")?
+            .get_tree()
+            .context("Can't get tree")?;
+
+        let mut windows: Vec<WindowItem> = vec![];
+
+        collect_windows(&root_node, None, &mut windows);
+
+        Ok(windows)
+    }
+
+    fn actions(&self, _item: &WindowItem) -> Vec<Action<WindowItem>> {
+        vec![
+            Action {
+                id: "kill",
+                name: String::from("Kill"),
+                run: Arc::new(|item: &WindowItem| {
+                    item.command("kill")?;
+
+                    exit(0);
+                }),
+            },
+            Action {
+                id: "scratchpad",
+                name: String::from("Move to scratchpad"),
+                run: Arc::new(|item: &WindowItem| {
+                    item.command("move scratchpad")?;
+
+                    exit(0);
+                }),
+            },
+        ]
+    }
+
+    fn marks(&self) -> bool {
+        true
+    }
+
+    fn exec_marked(&self, items: &[WindowItem]) -> anyhow::Result<()> {
+        let mut connection = Connection::new().context("Can't connect to WM socket")?;
+        let command = if self.kill_marked {
+            String::from("kill")
+        } else {
+            let workspace = connection
+                .get_workspaces()
+                .context("Can't get workspaces")?
+                .into_iter()
+                .find(|workspace| workspace.focused)
+                .context("No workspace is focused")?;
+            format!(
+                "move container to workspace \"{}\"",
+                workspace.name.replace('\\', "\\\\").replace('"', "\\\"")
+            )
+        };
+        // One command for every window, so they move or close together
+        let commands: Vec<String> = items
+            .iter()
+            .map(|item| format!("[con_id={}] {}", item.id, command))
+            .collect();
+        let outcomes = connection
+            .run_command(commands.join("; "))
+            .context("Failed to send command")?;
+        for outcome in outcomes {
+            outcome.context("Unable to take the marked windows")?;
+        }
+
+        exit(0);
+    }
+}
+
+// Collect the windows under the node, noting the workspace each is on
+fn collect_windows(parent: &Node, workspace: Option<&str>, container: &mut Vec<WindowItem>) {
+    if window_node_filter(parent) {
+        container.push(WindowItem::new(parent.to_owned(), workspace));
+    }
+
+    let workspace = if parent.node_type == NodeType::Workspace {
+        parent.name.as_deref()
+    } else {
+        workspace
+    };
+    for node in parent.nodes.iter() {
+        collect_windows(node, workspace, container);
+    }
+}
+
+fn window_node_filter(node: &Node) -> bool {
+    if let Some(window_props) = &node.window_properties {
+        let Some(window_type) = &window_props.window_type else {
+            return false;
+        };
+        let Some(window_title) = &window_props.title else {
+            return false;
+        };
+        (node.node_type == NodeType::Con || node.node_type == NodeType::FloatingCon)
+            && (window_type == "normal"
+                || window_type == "unknown"
+                || IS_WAYLAND && node.layout == NodeLayout::None)
+            && window_title != "i3bar"
+    } else {
+        (node.node_type == NodeType::Con || node.node_type == NodeType::FloatingCon)
+            && (IS_WAYLAND && node.layout == NodeLayout::None)
+    }
+}
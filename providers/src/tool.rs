@@ -0,0 +1,20 @@
+//! Running the command line tools which some providers drive
+use std::process::Command;
+
+use anyhow::{bail, Context};
+
+/// Run the tool, failing if it can't be run or fails, and return what it printed
+pub(crate) fn run(program: &str, args: &[&str]) -> anyhow::Result<String> {
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .with_context(|| format!("Unable to run {}", program))?;
+    if !output.status.success() {
+        bail!(
+            "{} failed: {}",
+            program,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
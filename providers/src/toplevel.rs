@@ -0,0 +1,182 @@
+use std::process::exit;
+use std::sync::Arc;
+
+use anyhow::Context;
+use common::{ItemDescriptor, ItemProvider};
+use wayland_client::globals::{registry_queue_init, GlobalListContents};
+use wayland_client::protocol::{wl_registry, wl_seat};
+use wayland_client::{event_created_child, Connection, Dispatch, EventQueue, QueueHandle};
+use wayland_protocols_wlr::foreign_toplevel::v1::client::zwlr_foreign_toplevel_handle_v1::{
+    self, ZwlrForeignToplevelHandleV1,
+};
+use wayland_protocols_wlr::foreign_toplevel::v1::client::zwlr_foreign_toplevel_manager_v1::{
+    self, ZwlrForeignToplevelManagerV1,
+};
+
+/// A window listed by the wlr foreign toplevel protocol, for compositors other than sway
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ToplevelItem {
+    title: Arc<str>,
+    app_id: Arc<str>,
+}
+
+impl ItemDescriptor for ToplevelItem {
+    fn title(&self) -> &str {
+        if self.title.is_empty() {
+            &self.app_id
+        } else {
+            &self.title
+        }
+    }
+
+    /// The app id, unless it already stands in for a missing title
+    fn subtitle(&self) -> Option<&str> {
+        (!self.title.is_empty() && !self.app_id.is_empty()).then_some(&*self.app_id)
+    }
+
+    fn keywords(&self) -> Option<&str> {
+        Some(&self.app_id)
+    }
+
+    fn icon(&self) -> Option<&str> {
+        (!self.app_id.is_empty()).then_some(&*self.app_id)
+    }
+
+    /// The protocol has no stable window ids, so activate the first window matching this one
+    fn exec(&self) -> anyhow::Result<()> {
+        let (mut toplevels, mut queue) = enumerate()?;
+        let seat = toplevels
+            .seat
+            .clone()
+            .context("No seat to activate the window on")?;
+        let (handle, _) = toplevels
+            .windows
+            .iter()
+            .find(|(_, window)| window == self)
+            .context("Window has closed")?;
+
+        handle.activate(&seat);
+        queue
+            .roundtrip(&mut toplevels)
+            .context("Failed to activate window")?;
+
+        exit(0);
+    }
+}
+
+/// Provides the windows of a wlroots compositor implementing `zwlr_foreign_toplevel_manager_v1`
+#[derive(Debug, Default, Clone)]
+pub struct ForeignToplevelProvider;
+
+impl ItemProvider for ForeignToplevelProvider {
+    type Item = ToplevelItem;
+
+    fn load(&self) -> anyhow::Result<Vec<ToplevelItem>> {
+        let (toplevels, _) = enumerate()?;
+        Ok(toplevels
+            .windows
+            .into_iter()
+            .map(|(_, window)| window)
+            .collect())
+    }
+}
+
+/// The state of the protocol objects collected while enumerating windows
+#[derive(Debug, Default)]
+struct Toplevels {
+    seat: Option<wl_seat::WlSeat>,
+    windows: Vec<(ZwlrForeignToplevelHandleV1, ToplevelItem)>,
+}
+
+/// Connect to the compositor and collect the current toplevels and their properties
+fn enumerate() -> anyhow::Result<(Toplevels, EventQueue<Toplevels>)> {
+    let connection = Connection::connect_to_env().context("Can't connect to Wayland display")?;
+    let (globals, mut queue) =
+        registry_queue_init::<Toplevels>(&connection).context("Can't list Wayland globals")?;
+    let qh = queue.handle();
+
+    let mut toplevels = Toplevels {
+        seat: globals.bind::<wl_seat::WlSeat, _, _>(&qh, 1..=1, ()).ok(),
+        windows: vec![],
+    };
+    globals
+        .bind::<ZwlrForeignToplevelManagerV1, _, _>(&qh, 1..=3, ())
+        .context("Compositor does not support wlr foreign toplevel management")?;
+
+    // The first roundtrip announces the toplevels, the second delivers their properties
+    queue.roundtrip(&mut toplevels)?;
+    queue.roundtrip(&mut toplevels)?;
+
+    Ok((toplevels, queue))
+}
+
+impl Dispatch<wl_registry::WlRegistry, GlobalListContents> for Toplevels {
+    fn event(
+        _: &mut Self,
+        _: &wl_registry::WlRegistry,
+        _: wl_registry::Event,
+        _: &GlobalListContents,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<wl_seat::WlSeat, ()> for Toplevels {
+    fn event(
+        _: &mut Self,
+        _: &wl_seat::WlSeat,
+        _: wl_seat::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<ZwlrForeignToplevelManagerV1, ()> for Toplevels {
+    fn event(
+        state: &mut Self,
+        _: &ZwlrForeignToplevelManagerV1,
+        event: zwlr_foreign_toplevel_manager_v1::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        if let zwlr_foreign_toplevel_manager_v1::Event::Toplevel { toplevel } = event {
+            state.windows.push((toplevel, ToplevelItem::default()));
+        }
+    }
+
+    event_created_child!(Toplevels, ZwlrForeignToplevelManagerV1, [
+        zwlr_foreign_toplevel_manager_v1::EVT_TOPLEVEL_OPCODE => (ZwlrForeignToplevelHandleV1, ()),
+    ]);
+}
+
+impl Dispatch<ZwlrForeignToplevelHandleV1, ()> for Toplevels {
+    fn event(
+        state: &mut Self,
+        handle: &ZwlrForeignToplevelHandleV1,
+        event: zwlr_foreign_toplevel_handle_v1::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        match event {
+            zwlr_foreign_toplevel_handle_v1::Event::Closed => {
+                state.windows.retain(|(candidate, _)| candidate != handle);
+            }
+            zwlr_foreign_toplevel_handle_v1::Event::Title { title } => {
+                if let Some((_, window)) = state.windows.iter_mut().find(|(h, _)| h == handle) {
+                    window.title = common::intern(&title);
+                }
+            }
+            zwlr_foreign_toplevel_handle_v1::Event::AppId { app_id } => {
+                if let Some((_, window)) = state.windows.iter_mut().find(|(h, _)| h == handle) {
+                    window.app_id = common::intern(&app_id);
+                }
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,59 @@
+//! Development tasks of the workspace, run with `cargo xtask <task>`
+use std::path::PathBuf;
+
+use anyhow::Context;
+use clap::{CommandFactory, Parser, Subcommand};
+
+#[derive(Debug, Parser)]
+struct Args {
+    #[command(subcommand)]
+    task: Task,
+}
+
+#[derive(Debug, Subcommand)]
+enum Task {
+    /// Write the man page of every binary, generated from its command line
+    Man {
+        /// Directory to write the pages into
+        #[arg(long, default_value = "target/man")]
+        out: PathBuf,
+    },
+}
+
+/// The command line of every binary, as its --help shows it
+fn commands() -> [clap::Command; 14] {
+    [
+        ilia::Args::command(),
+        ilia_adjust::Args::command(),
+        ilia_autostart::Args::command(),
+        ilia_context::Args::command(),
+        ilia_dmenu::Args::command(),
+        ilia_drun::Args::command(),
+        ilia_media::Args::command(),
+        ilia_mpd::Args::command(),
+        ilia_pinentry::Args::command(),
+        ilia_portal::Args::command(),
+        ilia_proc::Args::command(),
+        ilia_repos::Args::command(),
+        ilia_run::Args::command(),
+        ilia_windows::Args::command(),
+    ]
+}
+
+fn main() -> anyhow::Result<()> {
+    match Args::parse().task {
+        Task::Man { out } => {
+            std::fs::create_dir_all(&out)
+                .with_context(|| format!("Failed to create {}", out.display()))?;
+            for command in commands() {
+                let path = out.join(format!("{}.1", command.get_name()));
+                let mut page = vec![];
+                clap_mangen::Man::new(command).render(&mut page)?;
+                std::fs::write(&path, page)
+                    .with_context(|| format!("Failed to write {}", path.display()))?;
+                println!("{}", path.display());
+            }
+        }
+    }
+    Ok(())
+}